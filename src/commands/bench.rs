@@ -0,0 +1,211 @@
+//! Load testing with synthetic events, sent through the regular streaming engine
+use anyhow::Result;
+use clap::Args;
+use colored::*;
+use futures_util::stream::{self, Stream};
+use indicatif::{ProgressBar, ProgressStyle};
+use pynthora_terminal::core::config::Config;
+use pynthora_terminal::core::telemetry::TelemetryEvent;
+use pynthora_terminal::sdk::client::Client;
+use pynthora_terminal::sdk::streaming::{ProgressReporter, StreamOptions};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::{sleep_until, Instant};
+
+use super::stream::{batch_size_for, retry_config, shutdown_on_ctrl_c, SHUTDOWN_DRAIN_TIMEOUT};
+
+/// `source` of every synthetic event, so bench traffic is easy to filter out
+const BENCH_SOURCE: &str = "pynthora-bench";
+
+/// Arguments for `pynthora-terminal bench`
+#[derive(Debug, Args)]
+pub struct BenchArgs {
+    /// Target events per second
+    #[arg(long)]
+    pub rate: u64,
+    /// How long to generate traffic, in seconds
+    #[arg(long)]
+    pub duration: u64,
+    /// Pipeline ID to use
+    #[arg(short, long)]
+    pub pipeline: Option<String>,
+    /// Number of batches to send concurrently
+    #[arg(long, default_value_t = 4)]
+    pub concurrency: usize,
+    /// Events per batch (defaults to about 100ms worth of traffic, at most 100 or the
+    /// config's `batch_limits.max_events`)
+    #[arg(long)]
+    pub batch_size: Option<usize>,
+}
+
+pub async fn run(args: &BenchArgs) -> Result<()> {
+    if args.rate == 0 || args.duration == 0 {
+        anyhow::bail!("--rate and --duration must be greater than zero");
+    }
+
+    let config = Config::load(None)?;
+    let limits = config.batch_limits.clone().unwrap_or_default();
+    let client = Client::new(config)?;
+
+    let total = args.rate * args.duration;
+    // Batches only go out once full, so keep them small enough to leave at a steady pace
+    let batch_size = match (args.batch_size, limits.max_events) {
+        (Some(size), Some(max)) if size > max => anyhow::bail!(
+            "--batch-size {} exceeds batch_limits.max_events ({}) in the config",
+            size,
+            max
+        ),
+        (Some(size), _) => size,
+        (None, _) => (args.rate / 10).clamp(1, batch_size_for(&limits) as u64) as usize,
+    };
+
+    println!(
+        "{} Sending {} synthetic events at {} events/s for {}s (batches of {})...",
+        "ℹ".blue(),
+        total,
+        args.rate,
+        args.duration,
+        batch_size
+    );
+
+    let pb = ProgressBar::new(total);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} {msg}")
+            .unwrap(),
+    );
+
+    let latencies = Arc::new(Mutex::new(Vec::new()));
+    let opts = StreamOptions {
+        pipeline: args.pipeline.clone(),
+        batch_size,
+        concurrency: args.concurrency,
+        retry: retry_config(),
+        limits,
+        progress: Arc::new(BenchReporter {
+            pb: pb.clone(),
+            latencies: latencies.clone(),
+        }),
+        shutdown: Some(shutdown_on_ctrl_c(pb.clone())),
+        drain_timeout: SHUTDOWN_DRAIN_TIMEOUT,
+        ..Default::default()
+    };
+
+    let stats = client
+        .stream_all(synthetic_events(args.rate, total), opts)
+        .await?;
+    pb.finish_and_clear();
+
+    let mut latencies = std::mem::take(&mut *latencies.lock().unwrap());
+    latencies.sort();
+    let seconds = stats.elapsed.as_secs_f64().max(f64::EPSILON);
+
+    let interrupted = if stats.interrupted { " (interrupted)" } else { "" };
+    println!("{} Bench complete{}", "✓".green(), interrupted);
+    println!(
+        "  Throughput: {:.1} events/s sent, {:.1} events/s delivered (target {})",
+        stats.total as f64 / seconds,
+        stats.successful as f64 / seconds,
+        args.rate
+    );
+    println!(
+        "  Events: {} sent, {} delivered, {} failed in {} batches over {:.1}s",
+        stats.total, stats.successful, stats.failed, stats.batches, seconds
+    );
+    if !latencies.is_empty() {
+        println!(
+            "  Batch latency: p50 {:?}  p90 {:?}  p99 {:?}  max {:?}",
+            percentile(&latencies, 50.0),
+            percentile(&latencies, 90.0),
+            percentile(&latencies, 99.0),
+            latencies[latencies.len() - 1]
+        );
+    }
+    if stats.failed > 0 {
+        println!("{} {} events failed", "✗".red(), stats.failed);
+    }
+
+    Ok(())
+}
+
+/// `total` events paced at `rate` per second; a generator that falls behind (because
+/// sends are backpressuring it) catches up without sleeping
+fn synthetic_events(rate: u64, total: u64) -> impl Stream<Item = Value> {
+    let started = Instant::now();
+    stream::unfold((StdRng::from_entropy(), 0u64), move |(mut rng, seq)| async move {
+        if seq >= total {
+            return None;
+        }
+        sleep_until(started + Duration::from_secs_f64(seq as f64 / rate as f64)).await;
+        let event = synthetic_event(&mut rng, seq);
+        Some((event, (rng, seq + 1)))
+    })
+}
+
+/// A valid event with a random payload
+fn synthetic_event(rng: &mut impl Rng, seq: u64) -> Value {
+    let data = serde_json::json!({
+        "seq": seq,
+        "sensor": format!("sensor-{}", rng.gen_range(0..16)),
+        "value": rng.gen::<f64>() * 100.0,
+        "ok": rng.gen_bool(0.95),
+    });
+
+    let mut event = serde_json::to_value(TelemetryEvent::new("bench", data))
+        .expect("TelemetryEvent serializes to JSON");
+    event["source"] = Value::String(BENCH_SOURCE.to_string());
+    event
+}
+
+/// Nearest-rank percentile of sorted, non-empty samples
+fn percentile(sorted: &[Duration], pct: f64) -> Duration {
+    let rank = ((pct / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Drives the progress bar and records per-batch latency
+struct BenchReporter {
+    pb: ProgressBar,
+    latencies: Arc<Mutex<Vec<Duration>>>,
+}
+
+impl ProgressReporter for BenchReporter {
+    fn on_batch_complete(&self, done: usize, _total: usize) {
+        self.pb.set_position(done as u64);
+    }
+
+    fn on_error(&self, batch_num: usize, error: &str) {
+        self.pb.set_message(format!("Batch {} failed: {}", batch_num, error));
+    }
+
+    fn on_batch_latency(&self, _batch_num: usize, latency: Duration) {
+        self.latencies.lock().unwrap().push(latency);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pynthora_terminal::core::validation::validate_event;
+
+    #[test]
+    fn test_synthetic_events_are_valid() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for seq in 0..10 {
+            let event = synthetic_event(&mut rng, seq);
+            assert!(validate_event(&event).is_valid);
+            assert_eq!(event["data"]["seq"], seq);
+        }
+    }
+
+    #[test]
+    fn test_percentile() {
+        let samples: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&samples, 50.0), Duration::from_millis(50));
+        assert_eq!(percentile(&samples, 99.0), Duration::from_millis(99));
+        assert_eq!(percentile(&samples[..1], 90.0), Duration::from_millis(1));
+    }
+}
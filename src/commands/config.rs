@@ -0,0 +1,74 @@
+use anyhow::Result;
+use colored::*;
+use pynthora_terminal::core::config::{Config, Overrides};
+use pynthora_terminal::core::error::Error;
+use serde_json::Value;
+
+/// Header names whose values are masked like the API key
+const SENSITIVE_HEADER_HINTS: &[&str] = &["auth", "key", "token", "secret", "cookie"];
+
+/// Load and validate a config without using it for any network calls
+pub async fn validate(path: Option<&str>, overrides: &Overrides) -> Result<()> {
+    let (source, errors, warnings) =
+        Config::check(path, overrides).map_err(|e| Error::Config(format!("{:#}", e)))?;
+
+    println!("{} Config loaded from {}", "ℹ".blue(), source);
+
+    if !errors.is_empty() {
+        println!("{} Config is invalid:", "✗".red());
+        for error in &errors {
+            println!("  - {}", error);
+        }
+        return Err(Error::Config(format!("{} validation errors", errors.len())).into());
+    }
+
+    println!("{} Config is valid", "✓".green());
+    for warning in &warnings {
+        println!("{} {}", "⚠".yellow(), warning);
+    }
+    Ok(())
+}
+
+/// Print the effective config with secrets masked and the origin of each setting
+pub async fn show(path: Option<&str>, overrides: &Overrides) -> Result<()> {
+    let (source, config, origins) =
+        Config::explain(path, overrides).map_err(|e| Error::Config(format!("{:#}", e)))?;
+
+    println!("{} Config loaded from {}", "ℹ".blue(), source);
+
+    let Value::Object(mut fields) = serde_json::to_value(&config)? else {
+        unreachable!("Config serializes to an object");
+    };
+    fields.insert("api_key".to_string(), Value::String(mask(&config.api_key)));
+    if let Some(Value::Object(headers)) = fields.get_mut("extra_headers") {
+        for (name, value) in headers.iter_mut() {
+            let lower = name.to_lowercase();
+            if SENSITIVE_HEADER_HINTS.iter().any(|hint| lower.contains(hint)) {
+                *value = Value::String(mask(value.as_str().unwrap_or_default()));
+            }
+        }
+    }
+
+    for (key, value) in &fields {
+        let origin = origins
+            .get(key)
+            .map(|origin| origin.to_string())
+            .unwrap_or_else(|| "default".to_string());
+        let value = match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        println!("  {}: {} {}", key.bold(), value, format!("({})", origin).dimmed());
+    }
+
+    Ok(())
+}
+
+/// Keep the first 4 characters so keys can be told apart, hide the rest
+fn mask(secret: &str) -> String {
+    if secret.chars().count() <= 8 {
+        return "****".to_string();
+    }
+    let prefix: String = secret.chars().take(4).collect();
+    format!("{}****", prefix)
+}
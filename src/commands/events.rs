@@ -0,0 +1,363 @@
+use anyhow::{Context, Result};
+use colored::*;
+use pynthora_terminal::commands::stream::open_with_retry;
+use pynthora_terminal::commands::OutputFormat;
+use pynthora_terminal::core::config::Config;
+use pynthora_terminal::core::error::{self, Error};
+use pynthora_terminal::core::input::InputFormat;
+use pynthora_terminal::core::validation::{validate_event, BatchLimits, ValidationResult};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Layouts `events convert` can write
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ConvertFormat {
+    /// One JSON event per line
+    Ndjson,
+    /// A single top-level JSON array of events
+    Json,
+}
+
+impl fmt::Display for ConvertFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ConvertFormat::Ndjson => "ndjson",
+            ConvertFormat::Json => "json",
+        })
+    }
+}
+
+/// Validate an events file without streaming it
+pub async fn validate(
+    file: &str,
+    format: Option<InputFormat>,
+    strict: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    if output == OutputFormat::Human {
+        println!("{} Validating events in {}...", "ℹ".blue(), file);
+    }
+
+    // Works without a config too; one only adds its size limits
+    let limits = Config::load(None)
+        .ok()
+        .and_then(|config| config.batch_limits.clone())
+        .unwrap_or_default();
+
+    // Each event is checked on its own: the file is split into batches when it is
+    // streamed, so its total size says nothing about the batches that will be sent
+    let mut validation = ValidationResult::new();
+    let mut events = 0;
+    let mut parse_errors = Vec::new();
+
+    for item in open_with_retry(file, format).await? {
+        match item {
+            Ok(event) => {
+                check_event(&event, events, &limits, &mut validation);
+                events += 1;
+            }
+            Err(Error::Io(e)) => return Err(e).context("Failed to read file"),
+            Err(e) => parse_errors.push(e.to_string()),
+        }
+    }
+    if events == 0 {
+        validation.add_error("File contains no events".to_string());
+    }
+
+    if strict {
+        validation = validation.into_strict();
+    }
+
+    if output == OutputFormat::Json {
+        // Parse errors are reported alongside validation errors so tooling sees one list
+        let mut errors: Vec<String> = parse_errors
+            .iter()
+            .map(|e| format!("Parse error: {}", e))
+            .collect();
+        errors.append(&mut validation.errors);
+        validation.is_valid = errors.is_empty();
+        validation.errors = errors;
+
+        println!("{}", serde_json::to_string_pretty(&validation)?);
+        if !validation.is_valid {
+            return Err(Error::Validation(format!(
+                "{} validation errors",
+                validation.errors.len()
+            ))
+            .into());
+        }
+        return Ok(());
+    }
+
+    if !parse_errors.is_empty() {
+        println!("{} {} events failed to parse:", "✗".red(), parse_errors.len());
+        for error in &parse_errors {
+            println!("  - {}", error);
+        }
+    }
+
+    if !validation.errors.is_empty() {
+        println!("{} Validation errors:", "✗".red());
+        for error in &validation.errors {
+            println!("  - {}", error);
+        }
+    }
+
+    if !validation.warnings.is_empty() {
+        println!("{} Validation warnings:", "⚠".yellow());
+        for warning in &validation.warnings {
+            println!("  - {}", warning);
+        }
+    }
+
+    if !parse_errors.is_empty() || !validation.is_valid {
+        return Err(Error::Validation(format!(
+            "{} parse errors, {} validation errors",
+            parse_errors.len(),
+            validation.errors.len()
+        ))
+        .into());
+    }
+
+    println!(
+        "{} {} events passed validation!",
+        "✓".green(),
+        events
+    );
+
+    Ok(())
+}
+
+/// Validate the event at `index`, including that it fits in a batch under `limits`
+fn check_event(event: &Value, index: usize, limits: &BatchLimits, result: &mut ValidationResult) {
+    let event_result = validate_event(event);
+    for error in event_result.errors {
+        result.add_error(format!("Event {}: {}", index, error));
+    }
+    result.warnings.extend(event_result.warnings);
+
+    if let Some(max_bytes) = limits.max_total_bytes {
+        let bytes = event.to_string().len();
+        if bytes > max_bytes {
+            result.add_error(format!(
+                "Event {}: {} bytes exceeds the batch payload limit of {} bytes",
+                index, bytes, max_bytes
+            ));
+        }
+    }
+}
+
+/// Summarize an events file without sending anything.
+///
+/// Events are read one at a time, so memory stays bounded regardless of file size.
+pub async fn inspect(file: &str, format: Option<InputFormat>) -> Result<()> {
+    let size = fs::metadata(file)
+        .with_context(|| format!("Failed to open file: {}", file))?
+        .len();
+    let reader = open_with_retry(file, format).await?;
+    let format = reader.format();
+
+    let mut valid = 0;
+    let mut parse_errors = 0;
+    let mut event_types: BTreeMap<String, usize> = BTreeMap::new();
+
+    for item in reader {
+        match item {
+            Ok(event) => {
+                valid += 1;
+                let event_type = event["event_type"].as_str().unwrap_or("<none>");
+                *event_types.entry(event_type.to_string()).or_default() += 1;
+            }
+            Err(Error::Io(e)) => return Err(e).context("Failed to read file"),
+            Err(_) => parse_errors += 1,
+        }
+    }
+
+    println!("{} {}", "ℹ".blue(), file);
+    println!("  Format:       {}", format);
+    println!("  Size:         {} bytes", size);
+    println!("  Events:       {}", valid + parse_errors);
+    println!("  Parsed:       {}", valid);
+    println!("  Parse errors: {}", parse_errors);
+    println!("  Event types:  {}", event_types.len());
+    for (event_type, count) in &event_types {
+        println!("    {:<24} {}", event_type, count);
+    }
+
+    Ok(())
+}
+
+/// Split an events file into `parts` NDJSON shards with roughly equal event counts
+pub async fn split(
+    file: &str,
+    format: Option<InputFormat>,
+    parts: usize,
+    out_dir: &str,
+) -> Result<()> {
+    if parts == 0 {
+        return Err(Error::Validation("--parts must be at least 1".to_string()).into());
+    }
+
+    fs::create_dir_all(out_dir)
+        .with_context(|| format!("Failed to create directory: {}", out_dir))?;
+
+    // Count first so shards can be cut evenly without buffering events
+    let mut total: usize = 0;
+    for item in open_with_retry(file, format).await? {
+        match item {
+            Ok(_) => total += 1,
+            Err(Error::Io(e)) => return Err(e).context("Failed to read file"),
+            Err(_) => {}
+        }
+    }
+
+    let input = Path::new(file);
+    let name = input.file_name().and_then(|s| s.to_str()).unwrap_or("events");
+    let stem = name.split('.').next().unwrap_or("events");
+    let shard_path = |part: usize| Path::new(out_dir).join(format!("{}-{:03}.ndjson", stem, part + 1));
+
+    let per_part = total.div_ceil(parts).max(1);
+    let mut line_counts = vec![0usize; parts];
+    let mut writers = (0..parts)
+        .map(|part| Ok(BufWriter::new(File::create(shard_path(part))?)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut written = 0;
+    for item in open_with_retry(file, format).await? {
+        let Ok(event) = item else { continue };
+        let part = (written / per_part).min(parts - 1);
+        writeln!(writers[part], "{}", event)?;
+        line_counts[part] += 1;
+        written += 1;
+    }
+
+    for writer in &mut writers {
+        writer.flush()?;
+    }
+
+    for (part, lines) in line_counts.iter().enumerate() {
+        println!("  {} {} ({} lines)", "✓".green(), shard_path(part).display(), lines);
+    }
+    println!("{} Split {} into {} shards", "✓".green(), file, parts);
+
+    Ok(())
+}
+
+/// Rewrite an events file in another layout; `out` may be `-` for stdout
+pub async fn convert(
+    input: &str,
+    from: Option<InputFormat>,
+    to: ConvertFormat,
+    out: &str,
+) -> Result<()> {
+    let reader = open_with_retry(input, from).await?;
+    let from = reader.format();
+
+    let (written, skipped) = if out == "-" {
+        write_events(reader, to, io::stdout().lock())?
+    } else {
+        let file = File::create(out).with_context(|| format!("Failed to create file: {}", out))?;
+        write_events(reader, to, BufWriter::new(file))?
+    };
+
+    // Keep stdout clean when the converted events are written there
+    if skipped > 0 {
+        eprintln!("{} {} events failed to parse and were skipped", "⚠".yellow(), skipped);
+    }
+    if out != "-" {
+        println!(
+            "{} Converted {} events from {} to {} ({})",
+            "✓".green(),
+            written,
+            from,
+            to,
+            out
+        );
+    }
+
+    Ok(())
+}
+
+/// Write each parsed event to `writer` in `to`, returning (written, skipped) counts
+fn write_events(
+    events: impl Iterator<Item = error::Result<Value>>,
+    to: ConvertFormat,
+    mut writer: impl Write,
+) -> Result<(usize, usize)> {
+    let mut written = 0;
+    let mut skipped = 0;
+
+    if to == ConvertFormat::Json {
+        write!(writer, "[")?;
+    }
+    for item in events {
+        let event = match item {
+            Ok(event) => event,
+            Err(Error::Io(e)) => return Err(e).context("Failed to read file"),
+            Err(_) => {
+                skipped += 1;
+                continue;
+            }
+        };
+        match to {
+            ConvertFormat::Ndjson => writeln!(writer, "{}", event)?,
+            ConvertFormat::Json if written == 0 => write!(writer, "\n{}", event)?,
+            ConvertFormat::Json => write!(writer, ",\n{}", event)?,
+        }
+        written += 1;
+    }
+    if to == ConvertFormat::Json {
+        writeln!(writer, "{}]", if written == 0 { "" } else { "\n" })?;
+    }
+    writer.flush()?;
+
+    Ok((written, skipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pynthora_terminal::core::input::EventReader;
+    use std::io::Cursor;
+
+    fn convert_text(input: &str, from: InputFormat, to: ConvertFormat) -> (String, usize) {
+        let reader = EventReader::from_reader(Cursor::new(input.to_string()), from);
+        let mut out = Vec::new();
+        let (_, skipped) = write_events(reader, to, &mut out).unwrap();
+        (String::from_utf8(out).unwrap(), skipped)
+    }
+
+    #[test]
+    fn test_csv_to_ndjson() {
+        let (out, skipped) = convert_text(
+            "source,temperature\nrobot-1,21.5\nrobot-2,19\n",
+            InputFormat::Csv,
+            ConvertFormat::Ndjson,
+        );
+        assert_eq!(skipped, 0);
+        assert_eq!(
+            out,
+            "{\"source\":\"robot-1\",\"temperature\":21.5}\n\
+             {\"source\":\"robot-2\",\"temperature\":19}\n"
+        );
+    }
+
+    #[test]
+    fn test_ndjson_to_json_array_round_trips() {
+        let ndjson = "{\"a\":1}\nnot json\n{\"a\":2}\n";
+        let (out, skipped) = convert_text(ndjson, InputFormat::Ndjson, ConvertFormat::Json);
+        assert_eq!(skipped, 1);
+        let array: Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(array, serde_json::json!([{"a": 1}, {"a": 2}]));
+
+        let (out, _) = convert_text(&out, InputFormat::JsonArray, ConvertFormat::Ndjson);
+        assert_eq!(out, "{\"a\":1}\n{\"a\":2}\n");
+
+        let (empty, _) = convert_text("", InputFormat::Ndjson, ConvertFormat::Json);
+        assert_eq!(empty, "[]\n");
+    }
+}
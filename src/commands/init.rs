@@ -48,6 +48,7 @@ pub async fn run(force: bool) -> Result<()> {
         api_key,
         ingest_url,
         workspace,
+        ..Default::default()
     };
 
     config.validate().context("Invalid configuration")?;
@@ -1,4 +1,22 @@
+pub mod bench;
+pub mod config;
+pub mod events;
 pub mod init;
+pub mod ping;
 pub mod pipeline;
+pub mod repl;
+pub mod status;
 pub mod stream;
+pub mod usage;
+pub mod workspace;
+
+/// How validation-style commands report their results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Colored, human-readable lines
+    #[default]
+    Human,
+    /// A single JSON document on stdout, for CI and other tooling
+    Json,
+}
 
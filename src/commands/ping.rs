@@ -0,0 +1,30 @@
+use anyhow::Result;
+use colored::*;
+use pynthora_terminal::core::config::Config;
+use pynthora_terminal::sdk::client::Client;
+use std::time::Instant;
+
+/// Single health round-trip for readiness probes: prints OK/FAIL and latency
+pub async fn run() -> Result<()> {
+    let config = Config::load(None)?;
+    let client = Client::new(config)?;
+    check(&client).await
+}
+
+/// `ping` against an existing client
+pub async fn check(client: &Client) -> Result<()> {
+    let started = Instant::now();
+    let result = client.ping().await;
+    let latency = started.elapsed().as_millis();
+
+    match result {
+        Ok(_) => {
+            println!("{} {} ({}ms)", "OK".green(), client.base_url(), latency);
+            Ok(())
+        }
+        Err(e) => {
+            println!("{} {} ({}ms)", "FAIL".red(), client.base_url(), latency);
+            Err(e.into())
+        }
+    }
+}
@@ -1,39 +1,169 @@
 use anyhow::{Context, Result};
 use colored::*;
 use pynthora_terminal::core::config::Config;
+use pynthora_terminal::core::error::Error;
 use pynthora_terminal::core::validation::validate_pipeline;
 use pynthora_terminal::sdk::client::Client;
+use pynthora_terminal::sdk::pipelines::{parse_as, parse_auto, Syntax};
 use serde_json::Value;
 use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use tracing::instrument;
 
-pub async fn push(file: &str) -> Result<()> {
+use super::stream::warn_validation_skipped;
+use super::OutputFormat;
+
+/// File format for `pipeline export`
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// One `<id>.json` file per pipeline
+    Json,
+    /// One `<id>.yaml` file per pipeline
+    Yaml,
+    /// A single `pipelines.ndjson` with one pipeline per line
+    Ndjson,
+}
+
+/// Input format for pipeline definitions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum PipelineFormat {
+    /// Use the file extension, or detect JSON or YAML from content when there is none
+    Auto,
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl PipelineFormat {
+    /// The syntax forced by this format; `None` for `Auto`
+    fn syntax(self) -> Option<Syntax> {
+        match self {
+            PipelineFormat::Auto => None,
+            PipelineFormat::Json => Some(Syntax::Json),
+            PipelineFormat::Yaml => Some(Syntax::Yaml),
+            PipelineFormat::Toml => Some(Syntax::Toml),
+        }
+    }
+}
+
+/// Server-managed fields that never appear in local definitions
+const SERVER_FIELDS: &[&str] = &["id", "status", "created_at", "updated_at"];
+
+/// Step key that splices in the steps of another definition file
+const INCLUDE_KEY: &str = "$include";
+
+#[instrument]
+pub async fn push(
+    file: &str,
+    format: PipelineFormat,
+    strict: bool,
+    send_yaml: bool,
+    no_validate: bool,
+    skip_version_check: bool,
+    print_request: bool,
+) -> Result<()> {
     let config = Config::load(None)?;
-    let client = Client::new(config);
+    let mut client = Client::new(config)?;
+    if skip_version_check {
+        client = client.without_version_check();
+    }
+    if print_request {
+        client = client.with_request_printing();
+    }
+    client.check_server_version("pipeline push").await?;
 
     println!("{} Reading pipeline from {}...", "ℹ".blue(), file);
 
-    let content = fs::read_to_string(file)
-        .with_context(|| format!("Failed to read file: {}", file))?;
+    let content = read_pipeline_text(file)?;
+    let mut pipeline = parse_pipeline(&content, format, source_name(file))?;
+    let has_includes = resolve_includes(&mut pipeline, file)?;
 
-    let pipeline: Value = if file.ends_with(".yaml") || file.ends_with(".yml") {
-        serde_yaml::from_str(&content)
-            .with_context(|| format!("Failed to parse YAML: {}", file))?
+    if no_validate {
+        warn_validation_skipped();
     } else {
-        serde_json::from_str(&content)
-            .with_context(|| format!("Failed to parse JSON: {}", file))?
-    };
+        println!("{} Validating pipeline...", "ℹ".blue());
+        check_pipeline(&pipeline, strict)?;
+    }
+
+    println!("{} Pushing pipeline to server...", "ℹ".blue());
+
+    // Sending the original text keeps comments and anchors for the server's validator
+    let syntax = format
+        .syntax()
+        .unwrap_or_else(|| Syntax::detect(Path::new(file), &content));
+    let is_yaml = syntax == Syntax::Yaml;
+    if send_yaml && is_yaml && has_includes {
+        println!(
+            "{} Sending JSON: the server can't resolve {} in the original YAML",
+            "⚠".yellow(),
+            INCLUDE_KEY
+        );
+    }
+    let result = if send_yaml && is_yaml && !has_includes {
+        client.push_pipeline_yaml(&content, &pipeline).await
+    } else {
+        client.push_pipeline(&pipeline).await
+    }
+    .with_context(|| "Failed to push pipeline")?;
 
-    println!("{} Validating pipeline...", "ℹ".blue());
-    
+    println!("{} Pipeline pushed successfully!", "✓".green());
+    println!("  ID: {}", result.id);
+    println!("  Name: {}", result.name);
+    println!("  Version: {}", result.version);
+    println!("  Status: {}", result.status);
+
+    Ok(())
+}
+
+/// Validate a pipeline definition locally, without contacting the server
+pub async fn validate(
+    file: &str,
+    format: PipelineFormat,
+    strict: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    if output == OutputFormat::Human {
+        println!("{} Validating pipeline in {}...", "ℹ".blue(), file);
+        let pipeline = read_pipeline(file, format)?;
+        return check_pipeline(&pipeline, strict);
+    }
+
+    let pipeline = read_pipeline(file, format)?;
+    let mut validation = validate_pipeline(&pipeline);
+    if strict {
+        validation = validation.into_strict();
+    }
+
+    println!("{}", serde_json::to_string_pretty(&validation)?);
+    if !validation.is_valid {
+        return Err(Error::Validation(format!(
+            "pipeline has {} errors",
+            validation.errors.len()
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// Run pipeline validation and print the outcome; `strict` treats warnings as errors
+fn check_pipeline(pipeline: &Value, strict: bool) -> Result<()> {
     // Enhanced validation (v0.2.0)
-    let validation = validate_pipeline(&pipeline);
-    
+    let mut validation = validate_pipeline(pipeline);
+    if strict {
+        validation = validation.into_strict();
+    }
+
     if !validation.is_valid {
         println!("{} Validation failed:", "✗".red());
         for error in &validation.errors {
             println!("  - {}", error);
         }
-        anyhow::bail!("Pipeline validation failed");
+        return Err(Error::Validation(format!(
+            "pipeline has {} errors",
+            validation.errors.len()
+        ))
+        .into());
     }
 
     if !validation.warnings.is_empty() {
@@ -44,32 +174,551 @@ pub async fn push(file: &str) -> Result<()> {
     }
 
     println!("{} Pipeline validation passed!", "✓".green());
-    println!("{} Pushing pipeline to server...", "ℹ".blue());
-    
-    let result = client.push_pipeline(&pipeline).await
-        .with_context(|| "Failed to push pipeline")?;
-
-    println!("{} Pipeline pushed successfully!", "✓".green());
-    println!("  ID: {}", result.id);
-    println!("  Name: {}", result.name);
-    println!("  Version: {}", result.version);
-    println!("  Status: {}", result.status);
-
     Ok(())
 }
 
 pub async fn list() -> Result<()> {
-    let _config = Config::load(None)?;
+    let config = Config::load(None)?;
+    let client = Client::new(config)?;
+    list_with(&client).await
+}
+
+/// `list` against an existing client, so the REPL can reuse its connection pool
+pub async fn list_with(client: &Client) -> Result<()> {
     println!("{} Listing pipelines...", "ℹ".blue());
-    // TODO: Implement list with actual API call
-    println!("{} No pipelines found", "ℹ".yellow());
+    let pipelines = client
+        .list_pipelines()
+        .await
+        .context("Failed to list pipelines")?;
+
+    if pipelines.is_empty() {
+        println!("{} No pipelines found", "ℹ".yellow());
+        return Ok(());
+    }
+
+    println!("{:<24} {:<32} {:<10} {:<10}", "ID", "NAME", "VERSION", "STATUS");
+    for pipeline in &pipelines {
+        println!(
+            "{:<24} {:<32} {:<10} {:<10}",
+            pipeline.id, pipeline.name, pipeline.version, pipeline.status
+        );
+    }
     Ok(())
 }
 
 pub async fn show(id: &str) -> Result<()> {
-    let _config = Config::load(None)?;
+    let config = Config::load(None)?;
+    let client = Client::new(config)?;
+    show_with(&client, id).await
+}
+
+/// `show` against an existing client
+pub async fn show_with(client: &Client, id: &str) -> Result<()> {
     println!("{} Showing pipeline: {}", "ℹ".blue(), id);
-    // TODO: Implement show with actual API call
-    println!("{} Pipeline not found", "✗".red());
+    let pipeline = match client.get_pipeline(id).await {
+        Ok(pipeline) => pipeline,
+        Err(Error::Server { status: 404, .. }) => {
+            println!("{} Pipeline not found", "✗".red());
+            return Ok(());
+        }
+        Err(e) => return Err(e).with_context(|| format!("Failed to fetch pipeline {}", id)),
+    };
+
+    println!("{}", serde_yaml::to_string(&pipeline)?);
     Ok(())
 }
+
+pub async fn runs(id: &str, limit: usize) -> Result<()> {
+    let config = Config::load(None)?;
+    let client = Client::new(config)?;
+    runs_with(&client, id, limit).await
+}
+
+/// `runs` against an existing client
+pub async fn runs_with(client: &Client, id: &str, limit: usize) -> Result<()> {
+    let runs = match client.get_pipeline_runs(id, limit).await {
+        Ok(runs) => runs,
+        Err(Error::Server { status: 501, .. }) => {
+            anyhow::bail!("This server does not support pipeline run history")
+        }
+        Err(Error::Server { status: 404, .. }) => anyhow::bail!(
+            "No run history for pipeline {} (unknown pipeline, or the server doesn't support run history)",
+            id
+        ),
+        Err(e) => return Err(e).context("Failed to fetch pipeline runs"),
+    };
+
+    if runs.is_empty() {
+        println!("{} No runs found for pipeline {}", "ℹ".yellow(), id);
+        return Ok(());
+    }
+
+    println!(
+        "{:<24} {:<10} {:<26} {:<26} {:>10} {:>8}",
+        "RUN", "STATUS", "STARTED", "FINISHED", "PROCESSED", "FAILED"
+    );
+    for run in &runs {
+        let status = match run.status.as_str() {
+            "succeeded" | "success" | "completed" => run.status.green(),
+            "failed" | "error" => run.status.red(),
+            _ => run.status.yellow(),
+        };
+        println!(
+            "{:<24} {:<10} {:<26} {:<26} {:>10} {:>8}",
+            run.id,
+            status,
+            run.started_at.as_deref().unwrap_or("-"),
+            run.finished_at.as_deref().unwrap_or("-"),
+            run.events_processed,
+            run.events_failed
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn diff(id: &str, file: &str) -> Result<()> {
+    let config = Config::load(None)?;
+    let client = Client::new(config)?;
+    diff_with(&client, id, file).await
+}
+
+/// `diff` against an existing client
+pub async fn diff_with(client: &Client, id: &str, file: &str) -> Result<()> {
+    let local = read_pipeline(file, PipelineFormat::Auto)?;
+
+    println!("{} Fetching deployed pipeline {}...", "ℹ".blue(), id);
+    let mut remote = client
+        .get_pipeline(id)
+        .await
+        .with_context(|| format!("Failed to fetch pipeline {}", id))?;
+
+    if let (Some(remote_obj), Some(local_obj)) = (remote.as_object_mut(), local.as_object()) {
+        remote_obj.retain(|key, _| {
+            !SERVER_FIELDS.contains(&key.as_str()) || local_obj.contains_key(key)
+        });
+    }
+
+    let mut changes = Vec::new();
+    diff_values("", &remote, &local, &mut changes);
+
+    if changes.is_empty() {
+        println!("{} No differences between {} and {}", "✓".green(), id, file);
+        return Ok(());
+    }
+
+    println!("{} {} changes ({} → {}):", "ℹ".blue(), changes.len(), id, file);
+    for change in &changes {
+        match change {
+            Change::Added(path, value) => {
+                println!("  {}", format!("+ {}: {}", path, value).green())
+            }
+            Change::Removed(path, value) => {
+                println!("  {}", format!("- {}: {}", path, value).red())
+            }
+            Change::Changed(path, old, new) => {
+                println!("  {}", format!("~ {}: {} → {}", path, old, new).yellow())
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn export(dir: &str, format: ExportFormat) -> Result<()> {
+    let config = Config::load(None)?;
+    let client = Client::new(config)?;
+
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create directory: {}", dir))?;
+
+    println!("{} Listing pipelines...", "ℹ".blue());
+    let pipelines = client
+        .list_pipelines()
+        .await
+        .context("Failed to list pipelines")?;
+
+    let mut ndjson = String::new();
+    for summary in &pipelines {
+        let definition = client
+            .get_pipeline(&summary.id)
+            .await
+            .with_context(|| format!("Failed to fetch pipeline {}", summary.id))?;
+
+        let file_stem = safe_file_name(&summary.id);
+        match format {
+            ExportFormat::Json => {
+                let path = Path::new(dir).join(format!("{}.json", file_stem));
+                fs::write(&path, serde_json::to_string_pretty(&definition)?)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+            }
+            ExportFormat::Yaml => {
+                let path = Path::new(dir).join(format!("{}.yaml", file_stem));
+                fs::write(&path, serde_yaml::to_string(&definition)?)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+            }
+            ExportFormat::Ndjson => {
+                ndjson.push_str(&serde_json::to_string(&definition)?);
+                ndjson.push('\n');
+            }
+        }
+        println!("  {} {} ({})", "✓".green(), summary.id, summary.name);
+    }
+
+    if matches!(format, ExportFormat::Ndjson) {
+        let path = Path::new(dir).join("pipelines.ndjson");
+        fs::write(&path, ndjson).with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+
+    println!(
+        "{} Exported {} pipelines to {}",
+        "✓".green(),
+        pipelines.len(),
+        dir
+    );
+    Ok(())
+}
+
+pub async fn import(dir: &str) -> Result<()> {
+    let config = Config::load(None)?;
+    let client = Client::new(config)?;
+
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory: {}", dir))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            matches!(
+                path.extension().and_then(|ext| ext.to_str()),
+                Some("json" | "yaml" | "yml" | "toml" | "ndjson")
+            )
+        })
+        .collect();
+    paths.sort();
+
+    let mut pushed = 0;
+    let mut failed = 0;
+
+    for path in paths {
+        let file = path.to_string_lossy().to_string();
+        let definitions = if file.ends_with(".ndjson") {
+            fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read file: {}", file))?
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| {
+                    serde_json::from_str(line)
+                        .with_context(|| format!("Failed to parse JSON line in {}", file))
+                })
+                .collect::<Result<Vec<Value>>>()?
+        } else {
+            vec![read_pipeline(&file, PipelineFormat::Auto)?]
+        };
+
+        for definition in definitions {
+            let validation = validate_pipeline(&definition);
+            if !validation.is_valid {
+                println!("  {} {}: {}", "✗".red(), file, validation.errors.join("; "));
+                failed += 1;
+                continue;
+            }
+
+            match client.push_pipeline(&definition).await {
+                Ok(result) => {
+                    println!("  {} {} → {}", "✓".green(), file, result.id);
+                    pushed += 1;
+                }
+                Err(e) => {
+                    println!("  {} {}: {}", "✗".red(), file, e);
+                    failed += 1;
+                }
+            }
+        }
+    }
+
+    println!("{} Imported {} pipelines from {}", "✓".green(), pushed, dir);
+    if failed > 0 {
+        anyhow::bail!("{} pipelines failed to import", failed);
+    }
+    Ok(())
+}
+
+/// Make a pipeline id safe to use as a file name
+fn safe_file_name(id: &str) -> String {
+    id.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Read and parse a pipeline definition, resolving includes; `-` reads it from stdin
+fn read_pipeline(file: &str, format: PipelineFormat) -> Result<Value> {
+    let content = read_pipeline_text(file)?;
+    let mut pipeline = parse_pipeline(&content, format, source_name(file))?;
+    resolve_includes(&mut pipeline, file)?;
+    Ok(pipeline)
+}
+
+/// Read a definition's raw text; `-` reads it from stdin
+fn read_pipeline_text(file: &str) -> Result<String> {
+    if file == "-" {
+        let mut content = String::new();
+        io::stdin()
+            .read_to_string(&mut content)
+            .context("Failed to read pipeline from stdin")?;
+        if content.trim().is_empty() {
+            return Err(Error::Validation("no pipeline definition on stdin".to_string()).into());
+        }
+        return Ok(content);
+    }
+
+    fs::read_to_string(file).with_context(|| format!("Failed to read file: {}", file))
+}
+
+fn source_name(file: &str) -> &str {
+    if file == "-" {
+        "<stdin>"
+    } else {
+        file
+    }
+}
+
+/// Parse a pipeline definition; `Auto` goes by the extension of `source`, and
+/// extensionless files (and stdin) are read as JSON or YAML depending on the content
+fn parse_pipeline(content: &str, format: PipelineFormat, source: &str) -> Result<Value> {
+    let path = Path::new(source);
+    let parsed = match format.syntax() {
+        Some(syntax) => parse_as(syntax, content),
+        None => parse_auto(path, content),
+    };
+
+    parsed.with_context(|| {
+        let syntax = format
+            .syntax()
+            .unwrap_or_else(|| Syntax::detect(path, content));
+        format!("Failed to parse {}: {}", syntax, source)
+    })
+}
+
+/// Replace `{"$include": "path"}` entries in `steps` with the steps of the referenced
+/// file, resolved relative to `file` (the working directory for stdin). Returns
+/// whether anything was included.
+fn resolve_includes(pipeline: &mut Value, file: &str) -> Result<bool> {
+    let Some(steps) = pipeline.get_mut("steps").and_then(Value::as_array_mut) else {
+        return Ok(false);
+    };
+    if !steps.iter().any(is_include) {
+        return Ok(false);
+    }
+
+    let (base, mut chain) = if file == "-" {
+        (PathBuf::from("."), Vec::new())
+    } else {
+        let path = fs::canonicalize(file)
+            .with_context(|| format!("Failed to read file: {}", file))?;
+        (path.parent().map(Path::to_path_buf).unwrap_or_default(), vec![path])
+    };
+
+    *steps = expand_steps(std::mem::take(steps), &base, &mut chain)?;
+    Ok(true)
+}
+
+fn is_include(step: &Value) -> bool {
+    step.get(INCLUDE_KEY).is_some()
+}
+
+/// Splice included steps into `steps`, recursing into included files. `chain` holds
+/// the files currently being expanded, so a file that includes itself, directly or
+/// not, is reported instead of recursing forever.
+fn expand_steps(steps: Vec<Value>, base: &Path, chain: &mut Vec<PathBuf>) -> Result<Vec<Value>> {
+    let mut expanded = Vec::with_capacity(steps.len());
+    for step in steps {
+        let Some(include) = step.get(INCLUDE_KEY) else {
+            expanded.push(step);
+            continue;
+        };
+        let include = include.as_str().ok_or_else(|| {
+            Error::Validation(format!("'{}' must be a file path, got {}", INCLUDE_KEY, include))
+        })?;
+
+        let path = fs::canonicalize(base.join(include))
+            .with_context(|| format!("Failed to read included file: {}", include))?;
+        if let Some(start) = chain.iter().position(|seen| *seen == path) {
+            let cycle: Vec<String> = chain[start..]
+                .iter()
+                .chain([&path])
+                .map(|p| p.display().to_string())
+                .collect();
+            return Err(
+                Error::Validation(format!("include cycle: {}", cycle.join(" -> "))).into(),
+            );
+        }
+
+        let name = path.display().to_string();
+        let content = read_pipeline_text(&name)?;
+        let steps = match parse_pipeline(&content, PipelineFormat::Auto, &name)? {
+            Value::Array(steps) => steps,
+            Value::Object(mut fragment) => match fragment.remove("steps") {
+                Some(Value::Array(steps)) => steps,
+                _ => return Err(not_a_fragment(&name)),
+            },
+            _ => return Err(not_a_fragment(&name)),
+        };
+
+        chain.push(path);
+        let base = chain[chain.len() - 1].parent().map(Path::to_path_buf).unwrap_or_default();
+        expanded.extend(expand_steps(steps, &base, chain)?);
+        chain.pop();
+    }
+    Ok(expanded)
+}
+
+fn not_a_fragment(file: &str) -> anyhow::Error {
+    Error::Validation(format!(
+        "included file {} must be a list of steps or have a 'steps' list",
+        file
+    ))
+    .into()
+}
+
+/// A single field-level difference between two pipeline definitions
+enum Change {
+    Added(String, Value),
+    Removed(String, Value),
+    Changed(String, Value, Value),
+}
+
+fn join_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+/// Recursively collect the changes needed to turn `old` into `new`
+fn diff_values(path: &str, old: &Value, new: &Value, changes: &mut Vec<Change>) {
+    match (old, new) {
+        (Value::Object(old_obj), Value::Object(new_obj)) => {
+            let mut keys: Vec<&String> = old_obj.keys().chain(new_obj.keys()).collect();
+            keys.sort();
+            keys.dedup();
+
+            for key in keys {
+                let child = join_path(path, key);
+                match (old_obj.get(key), new_obj.get(key)) {
+                    (Some(o), Some(n)) => diff_values(&child, o, n, changes),
+                    (Some(o), None) => changes.push(Change::Removed(child, o.clone())),
+                    (None, Some(n)) => changes.push(Change::Added(child, n.clone())),
+                    (None, None) => {}
+                }
+            }
+        }
+        (Value::Array(old_steps), Value::Array(new_steps)) if path == "steps" => {
+            diff_steps(path, old_steps, new_steps, changes);
+        }
+        (Value::Array(old_items), Value::Array(new_items)) => {
+            for idx in 0..old_items.len().max(new_items.len()) {
+                let child = format!("{}[{}]", path, idx);
+                match (old_items.get(idx), new_items.get(idx)) {
+                    (Some(o), Some(n)) => diff_values(&child, o, n, changes),
+                    (Some(o), None) => changes.push(Change::Removed(child, o.clone())),
+                    (None, Some(n)) => changes.push(Change::Added(child, n.clone())),
+                    (None, None) => {}
+                }
+            }
+        }
+        _ if old != new => changes.push(Change::Changed(path.to_string(), old.clone(), new.clone())),
+        _ => {}
+    }
+}
+
+/// Diff steps by name so reordering or inserting a step doesn't cascade into every index
+fn diff_steps(path: &str, old_steps: &[Value], new_steps: &[Value], changes: &mut Vec<Change>) {
+    let step_key = |idx: usize, step: &Value| -> String {
+        match step.get("name").and_then(Value::as_str) {
+            Some(name) => format!("{}[name={}]", path, name),
+            None => format!("{}[{}]", path, idx),
+        }
+    };
+
+    let old_keyed: Vec<(String, &Value)> = old_steps
+        .iter()
+        .enumerate()
+        .map(|(idx, step)| (step_key(idx, step), step))
+        .collect();
+    let new_keyed: Vec<(String, &Value)> = new_steps
+        .iter()
+        .enumerate()
+        .map(|(idx, step)| (step_key(idx, step), step))
+        .collect();
+
+    for (key, old_step) in &old_keyed {
+        match new_keyed.iter().find(|(k, _)| k == key) {
+            Some((_, new_step)) => diff_values(key, old_step, new_step, changes),
+            None => changes.push(Change::Removed(key.clone(), (*old_step).clone())),
+        }
+    }
+
+    for (key, new_step) in &new_keyed {
+        if !old_keyed.iter().any(|(k, _)| k == key) {
+            changes.push(Change::Added(key.clone(), (*new_step).clone()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_resolve_includes() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("steps")).unwrap();
+        fs::write(
+            dir.path().join("steps/common.yaml"),
+            "- name: clean\n  type: filter\n- $include: enrich.json\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("steps/enrich.json"),
+            r#"{"steps": [{"name": "geo", "type": "enrich"}]}"#,
+        )
+        .unwrap();
+        let root = dir.path().join("pipeline.yaml");
+        fs::write(&root, "").unwrap();
+
+        let mut pipeline = json!({
+            "name": "p",
+            "steps": [{"$include": "steps/common.yaml"}, {"name": "out", "type": "sink"}]
+        });
+        assert!(resolve_includes(&mut pipeline, root.to_str().unwrap()).unwrap());
+
+        let names: Vec<&str> = pipeline["steps"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|step| step["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["clean", "geo", "out"]);
+    }
+
+    #[test]
+    fn test_resolve_includes_rejects_cycles() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.yaml"), "- $include: b.yaml\n").unwrap();
+        fs::write(dir.path().join("b.yaml"), "- $include: a.yaml\n").unwrap();
+        let root = dir.path().join("pipeline.yaml");
+        fs::write(&root, "").unwrap();
+
+        let mut pipeline = json!({"name": "p", "steps": [{"$include": "a.yaml"}]});
+        let err = resolve_includes(&mut pipeline, root.to_str().unwrap()).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("include cycle"), "{}", message);
+        assert!(message.contains("a.yaml -> ") && message.ends_with("a.yaml"), "{}", message);
+    }
+}
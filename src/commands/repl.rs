@@ -0,0 +1,142 @@
+//! Interactive prompt that keeps one `Client`, and its connection pool, across commands
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use colored::*;
+use pynthora_terminal::core::config::Config;
+use pynthora_terminal::sdk::client::Client;
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+
+use super::{pipeline, ping, status};
+
+const PROMPT: &str = "pynthora> ";
+
+/// A line of REPL input; `pipeline` may prefix the pipeline commands as on the command line
+#[derive(Debug, Parser)]
+#[command(name = "repl", no_binary_name = true, disable_version_flag = true)]
+struct ReplLine {
+    #[command(subcommand)]
+    command: ReplCommand,
+}
+
+#[derive(Debug, PartialEq, Eq, Subcommand)]
+enum ReplCommand {
+    /// Check ingestion status and health
+    Status {
+        /// Show detailed metrics
+        #[arg(short, long)]
+        verbose: bool,
+    },
+    /// Quick connectivity check with round-trip latency
+    Ping,
+    /// List all pipelines
+    List,
+    /// Show pipeline details
+    Show {
+        /// Pipeline ID
+        id: String,
+    },
+    /// Show recent runs of a pipeline
+    Runs {
+        /// Pipeline ID
+        id: String,
+        /// Maximum number of runs to show
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Compare a deployed pipeline with a local definition
+    Diff {
+        /// Pipeline ID
+        id: String,
+        /// Local pipeline definition file (YAML or JSON)
+        file: String,
+    },
+    /// Leave the REPL
+    #[command(alias = "quit")]
+    Exit,
+}
+
+pub async fn run() -> Result<()> {
+    let config = Config::load(None)?;
+    let client = Client::new(config)?;
+    let mut editor = DefaultEditor::new()?;
+
+    println!(
+        "{} Connected to {} (workspace {}). Type `help` for commands, `exit` to quit.",
+        "ℹ".blue(),
+        client.base_url(),
+        client.workspace()
+    );
+
+    loop {
+        let line = match editor.readline(PROMPT) {
+            Ok(line) => line,
+            // Ctrl+C clears the current line; Ctrl+D leaves
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into()),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(line);
+
+        let command = match parse_line(line) {
+            Ok(command) => command,
+            Err(e) => {
+                // Covers `help` and `--help` too, which clap reports as errors
+                let _ = e.print();
+                continue;
+            }
+        };
+
+        let result = match command {
+            ReplCommand::Status { verbose } => status::report(&client, verbose).await,
+            ReplCommand::Ping => ping::check(&client).await,
+            ReplCommand::List => pipeline::list_with(&client).await,
+            ReplCommand::Show { id } => pipeline::show_with(&client, &id).await,
+            ReplCommand::Runs { id, limit } => pipeline::runs_with(&client, &id, limit).await,
+            ReplCommand::Diff { id, file } => pipeline::diff_with(&client, &id, &file).await,
+            ReplCommand::Exit => break,
+        };
+
+        // Errors end the command, not the session
+        if let Err(e) = result {
+            println!("{} {:#}", "✗".red(), e);
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_line(line: &str) -> Result<ReplCommand, clap::Error> {
+    let mut words: Vec<&str> = line.split_whitespace().collect();
+    if words.first() == Some(&"pipeline") {
+        words.remove(0);
+    }
+    ReplLine::try_parse_from(words).map(|parsed| parsed.command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(
+            parse_line("show abc").unwrap(),
+            ReplCommand::Show { id: "abc".to_string() }
+        );
+        assert_eq!(
+            parse_line("pipeline runs abc --limit 5").unwrap(),
+            ReplCommand::Runs {
+                id: "abc".to_string(),
+                limit: 5
+            }
+        );
+        assert_eq!(parse_line("quit").unwrap(), ReplCommand::Exit);
+        assert!(parse_line("show").is_err());
+        assert!(parse_line("stream --file x").is_err());
+    }
+}
@@ -0,0 +1,172 @@
+// Status command with real-time health monitoring (v0.2.0)
+use anyhow::Result;
+use colored::*;
+use indicatif::{ProgressBar, ProgressStyle};
+use pynthora_terminal::core::config::Config;
+use pynthora_terminal::sdk::client::Client;
+use std::collections::VecDeque;
+use std::time::Duration;
+use tokio::time::sleep;
+
+/// How often health is sampled while monitoring
+const MONITOR_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// How long a health snapshot is reused: one poll interval, so the gateway is asked
+/// at most once per sample
+const HEALTH_CACHE_TTL: Duration = MONITOR_POLL_INTERVAL;
+
+/// Shown when `--verbose` has no metrics to report
+const NO_METRICS_NOTE: &str =
+    "Server did not report metrics (may require a newer gateway or the metrics endpoint)";
+
+const SPARK_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Ring buffer of the most recent samples of a metric
+struct History {
+    samples: VecDeque<f64>,
+    capacity: usize,
+}
+
+impl History {
+    fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    fn push(&mut self, sample: f64) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// Render the samples as a sparkline scaled between their min and max
+    fn sparkline(&self) -> String {
+        let min = self.samples.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = self.samples.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let range = max - min;
+
+        self.samples
+            .iter()
+            .map(|sample| {
+                if range <= f64::EPSILON {
+                    SPARK_CHARS[0]
+                } else {
+                    let level = ((sample - min) / range * 7.0).round() as usize;
+                    SPARK_CHARS[level.min(7)]
+                }
+            })
+            .collect()
+    }
+}
+
+pub async fn run(verbose: bool, history: usize) -> Result<()> {
+    let config = Config::load(None)?;
+    let mut client = Client::new(config)?;
+    // The first monitoring sample can reuse the snapshot just printed
+    client = client.with_health_cache(HEALTH_CACHE_TTL);
+
+    report(&client, verbose).await?;
+
+    // Real-time monitoring mode (v0.2.0)
+    if verbose {
+        println!("\n{} Starting real-time monitoring (Ctrl+C to stop)...", "ℹ".blue());
+        
+        let pb = ProgressBar::new_spinner();
+        pb.set_style(
+            ProgressStyle::default_spinner()
+                .template("{spinner:.green} Monitoring... {msg}")
+                .unwrap(),
+        );
+
+        let mut rps_history = History::new(history);
+        let mut latency_history = History::new(history);
+
+        loop {
+            match client.health_check(false).await {
+                Ok(health) => {
+                    let status_icon = if health.status == "healthy" { "✓" } else { "✗" };
+                    let status_color = if health.status == "healthy" { "green" } else { "red" };
+                    
+                    let mut msg = format!("{} Status: {}", status_icon, health.status);
+                    if let Some(metrics) = health.reported_metrics() {
+                        if let Some(rps) = metrics.requests_per_second {
+                            rps_history.push(rps);
+                            msg.push_str(&format!(
+                                " | RPS: {:.2} {}",
+                                rps,
+                                rps_history.sparkline()
+                            ));
+                        }
+                        if let Some(latency) = metrics.latency_ms {
+                            latency_history.push(latency);
+                            msg.push_str(&format!(
+                                " | Latency: {:.2}ms {}",
+                                latency,
+                                latency_history.sparkline()
+                            ));
+                        }
+                    } else {
+                        msg.push_str(" | no metrics reported");
+                    }
+                    
+                    pb.set_message(msg);
+                }
+                Err(e) => {
+                    pb.set_message(format!("✗ Error: {}", e));
+                }
+            }
+            
+            sleep(MONITOR_POLL_INTERVAL).await;
+        }
+    }
+
+    Ok(())
+}
+
+/// Print a single health snapshot, plus metrics when `verbose`
+pub async fn report(client: &Client, verbose: bool) -> Result<()> {
+    println!("{} Checking pynthora terminal health...", "ℹ".blue());
+
+    // No added context: the error itself says whether the host is wrong or unreachable
+    let health = client.health_check(true).await?;
+
+    println!("\n{} Health Status", "=".cyan().bold());
+    println!("  Status: {}", 
+        if health.status == "healthy" { 
+            "✓ Healthy".green() 
+        } else { 
+            format!("✗ {}", health.status).red() 
+        }
+    );
+
+    if let Some(version) = &health.version {
+        println!("  Version: {}", version);
+    }
+
+    if let Some(uptime) = health.uptime {
+        let hours = uptime / 3600;
+        let minutes = (uptime % 3600) / 60;
+        println!("  Uptime: {}h {}m", hours, minutes);
+    }
+
+    if verbose {
+        if let Some(metrics) = health.reported_metrics() {
+            println!("\n{} Metrics", "=".cyan().bold());
+            if let Some(total) = metrics.requests_total {
+                println!("  Total Requests: {}", total);
+            }
+            if let Some(rps) = metrics.requests_per_second {
+                println!("  Requests/sec: {:.2}", rps);
+            }
+            if let Some(latency) = metrics.latency_ms {
+                println!("  Avg Latency: {:.2}ms", latency);
+            }
+        } else {
+            println!("\n{} {}", "ℹ".blue(), NO_METRICS_NOTE);
+        }
+    }
+
+    Ok(())
+}
@@ -1,62 +1,1592 @@
 use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset};
+use clap::Args;
 use colored::*;
+use flate2::Compression;
+use futures_util::{future, stream, Stream, StreamExt};
 use indicatif::{ProgressBar, ProgressStyle};
 use pynthora_terminal::core::config::Config;
-use pynthora_terminal::core::retry::{retry_with_backoff, RetryConfig};
-use pynthora_terminal::core::validation::validate_batch;
+use pynthora_terminal::core::dead_letter::{strip_error, DeadLetter};
+use pynthora_terminal::core::error::Error;
+use pynthora_terminal::core::history;
+use pynthora_terminal::core::input::{
+    expand_pattern, CleanedLines, EventReader, InputDigest, InputEncoding, InputFormat,
+};
+use pynthora_terminal::core::journal::Journal;
+use pynthora_terminal::core::projection::Projection;
+use pynthora_terminal::core::retry::{
+    is_retryable_io_error, retry_with_backoff, retry_with_backoff_if, BackoffStrategy,
+    RetryConfig,
+};
+use pynthora_terminal::core::schema::EventSchema;
+use pynthora_terminal::core::sent_log::SentLog;
+use pynthora_terminal::core::validation::{validate_batch, validate_event, BatchLimits};
 use pynthora_terminal::core::telemetry::TelemetryEvent;
-use pynthora_terminal::sdk::client::Client;
+use pynthora_terminal::sdk::client::{Client, Encoding, RejectedEvent};
+use pynthora_terminal::sdk::streaming::{
+    group_by_pipeline, AdaptiveConfig, ProgressReporter, StreamOptions, StreamStats,
+};
+use pynthora_terminal::sdk::signing::{load_signing_key, sign_value, SigningKey};
+use pynthora_terminal::sdk::websocket::{Delivery, WebSocketClient};
+use serde::Serialize;
 use serde_json::Value;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::time::Duration;
-use tracing::{debug, info, warn};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncSeekExt, BufReader as AsyncBufReader};
+use tokio::sync::watch;
+use tokio::time::sleep;
+use tracing::{debug, field, info, instrument, warn, Span};
+
+use super::usage::quota_warning;
 
 const DEFAULT_BATCH_SIZE: usize = 100;
+const DEFAULT_RETRIES: u32 = 2;
+const DEFAULT_RETRY_INITIAL_DELAY_MS: u64 = 100;
+const DEFAULT_RETRY_MAX_DELAY_MS: u64 = 5000;
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// How long in-flight batches get to finish after Ctrl+C
+pub const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+/// With --await-processing, how often batch status is polled and for how long
+const BATCH_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const AWAIT_PROCESSING_TIMEOUT: Duration = Duration::from_secs(600);
+/// Batch status requests in flight at once while polling
+const BATCH_POLL_CONCURRENCY: usize = 8;
+/// Rejected events listed individually before the rest are summarized
+const MAX_REJECTIONS_SHOWN: usize = 20;
+
+/// Arguments for `pynthora-terminal stream`
+#[derive(Debug, Args)]
+pub struct StreamArgs {
+    /// Input file path, or a quoted pattern like `'logs/*.ndjson'` to send every
+    /// matching file as one stream, with batches filling across files
+    #[arg(short, long)]
+    pub file: String,
+    /// Send the files matching --file one at a time, so no batch spans two files, and
+    /// stop at the first failed batch
+    #[arg(long, conflicts_with = "follow")]
+    pub fail_fast: bool,
+    /// Pipeline ID to use
+    #[arg(short, long)]
+    pub pipeline: Option<String>,
+    /// Route each event to the pipeline named in this field (stripped before sending);
+    /// events without it use --pipeline
+    #[arg(long)]
+    pub pipeline_field: Option<String>,
+    /// Keep the file open and stream lines as they are appended (like `tail -f`)
+    #[arg(long)]
+    pub follow: bool,
+    /// With --follow, send partial batches once this many seconds have passed
+    #[arg(long, requires = "follow")]
+    pub flush_interval: Option<u64>,
+    /// Number of batches to send concurrently
+    #[arg(long, default_value_t = 1)]
+    pub concurrency: usize,
+    /// Hold back new batches while batches in flight add up to this many serialized
+    /// bytes, for a memory ceiling with large events and high --concurrency
+    #[arg(long)]
+    pub max_in_flight_bytes: Option<usize>,
+    /// Tune batch size and concurrency automatically: start small, grow while batches
+    /// are fast, back off on 429s and failures
+    #[arg(long, conflicts_with = "concurrency")]
+    pub adaptive: bool,
+    /// Times to retry a failed batch before counting it as failed
+    #[arg(long, default_value_t = DEFAULT_RETRIES)]
+    pub retries: u32,
+    /// Delay before the first retry, in milliseconds; doubles on each further retry
+    #[arg(long, default_value_t = DEFAULT_RETRY_INITIAL_DELAY_MS)]
+    pub retry_initial_delay: u64,
+    /// Upper bound on the delay between retries, in milliseconds
+    #[arg(long, default_value_t = DEFAULT_RETRY_MAX_DELAY_MS)]
+    pub retry_max_delay: u64,
+    /// Abort before sending if more lines than this fail to parse (a count, or a
+    /// percentage like `5%`)
+    #[arg(long, conflicts_with = "follow")]
+    pub max_parse_errors: Option<ParseErrorLimit>,
+    /// Validate the first N events before sending anything, as a quick check on huge
+    /// files; the rest are still validated batch by batch as they are sent
+    #[arg(long, alias = "validate-only-first", value_name = "N")]
+    #[arg(conflicts_with_all = ["no_validate", "follow"])]
+    pub validate_sample: Option<usize>,
+    /// When a batch fails after its retries: keep going, or abort the run so no more
+    /// of the dataset is sent (exits non-zero)
+    #[arg(long, value_enum, default_value_t = OnError::Continue)]
+    pub on_error: OnError,
+    /// Warn before sending if the file has more events than remain in the workspace quota
+    #[arg(long, conflicts_with = "follow")]
+    pub check_quota: bool,
+    /// Only send this fraction (0.0-1.0) of events, chosen deterministically by content hash
+    #[arg(long, value_parser = parse_sample_rate)]
+    pub sample: Option<f64>,
+    /// Remove this field from every event before validating and sending (repeatable;
+    /// dotted paths like `data.email` reach into nested objects)
+    #[arg(long = "drop-field")]
+    pub drop_fields: Vec<String>,
+    /// Keep only these fields (comma-separated, dotted paths allowed); must include
+    /// timestamp, source and data
+    #[arg(long, value_delimiter = ',')]
+    pub keep_only: Vec<String>,
+    /// Add `key=value` to every event's metadata (repeatable)
+    #[arg(long = "meta", value_parser = parse_key_value)]
+    pub meta: Vec<(String, String)>,
+    /// Let --meta values replace metadata keys already present on events
+    #[arg(long)]
+    pub meta_override: bool,
+    /// Send to this workspace instead of the configured one (for cross-workspace backfills)
+    #[arg(long)]
+    pub target_workspace: Option<String>,
+    /// Send a header whose value is read from an environment variable on every request,
+    /// as `'Name: VAR'` (repeatable; quote it so the shell doesn't expand `$VAR`)
+    #[arg(long = "header-from-env", value_parser = parse_env_header)]
+    pub env_headers: Vec<(String, String)>,
+    /// Transport used to send events
+    #[arg(long, value_enum, default_value = "http")]
+    pub protocol: Protocol,
+    /// Gzip batch bodies before sending
+    #[arg(long)]
+    pub compress: bool,
+    /// Gzip level 0-9 (implies --compress): low levels favor throughput, high levels
+    /// favor bandwidth at more CPU per batch. Defaults to a balanced 6
+    #[arg(long)]
+    pub compression_level: Option<u32>,
+    /// Only send events with a `timestamp` at or after this RFC 3339 time
+    #[arg(long, value_parser = parse_rfc3339)]
+    pub since: Option<DateTime<FixedOffset>>,
+    /// Only send events with a `timestamp` before this RFC 3339 time
+    #[arg(long, value_parser = parse_rfc3339)]
+    pub until: Option<DateTime<FixedOffset>>,
+    /// With --since/--until, drop events that have no parseable `timestamp` (kept by default)
+    #[arg(long)]
+    pub require_timestamp: bool,
+    /// Input format (ndjson, json, csv); detected from the extension or content when
+    /// omitted. --follow reads NDJSON only
+    #[arg(long, conflicts_with = "follow")]
+    pub input_format: Option<InputFormat>,
+    /// Character encoding of the input (utf-8, latin1); a leading UTF-8 BOM is always
+    /// skipped. --follow reads UTF-8 only
+    #[arg(long, default_value_t = InputEncoding::Utf8, conflicts_with = "follow")]
+    pub input_encoding: InputEncoding,
+    /// Check every event against this JSON Schema (JSON or YAML) before sending; events
+    /// that fail are skipped and written to --dead-letter
+    #[arg(long, conflicts_with = "follow")]
+    pub event_schema: Option<PathBuf>,
+    /// Sign each event with Ed25519 and attach the signature as `signature`
+    #[arg(long)]
+    pub sign: bool,
+    /// Signing key for --sign (defaults to `signing_key_path` from the config)
+    #[arg(long, requires = "sign")]
+    pub signing_key: Option<PathBuf>,
+    /// Wire encoding for batch bodies (json, msgpack); msgpack needs gateway support
+    #[arg(long, default_value_t = Encoding::Json)]
+    pub encoding: Encoding,
+    /// Append events that could not be delivered to this NDJSON file, each with an
+    /// `_error` field
+    #[arg(long)]
+    pub dead_letter: Option<String>,
+    /// Append a line (idempotency key, SHA-256, ack time) for each event the gateway
+    /// accepts to this file
+    #[arg(long)]
+    pub journal: Option<String>,
+    /// After sending, poll the gateway until every batch is processed and report how
+    /// many events it processed and rejected
+    #[arg(long, conflicts_with = "follow")]
+    pub await_processing: bool,
+    /// Remember delivered events under this id (in ~/.pynthora/runs) and skip them
+    /// when a run with the same id is repeated; events get a content-derived
+    /// `metadata.idempotency_key`
+    #[arg(long, conflicts_with = "follow")]
+    pub run_id: Option<String>,
+    /// Don't refuse to run against gateways older than `min_server_version`
+    #[arg(long)]
+    pub skip_version_check: bool,
+    /// Skip client-side batch validation and let the server decide what it accepts
+    #[arg(long)]
+    pub no_validate: bool,
+    /// Print extra detail in the summary (e.g. compression savings)
+    #[arg(short, long)]
+    pub verbose: bool,
+    /// Dump the first request (and any failing one) to stderr, with secret headers redacted
+    #[arg(long)]
+    pub print_request: bool,
+    /// Report the run summary as human-readable text, a JSON object or a CSV row
+    #[arg(long, value_enum, default_value = "human", conflicts_with = "follow")]
+    pub output: SummaryFormat,
+}
+
+/// How `stream` reports its run summary
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SummaryFormat {
+    /// Colored, human-readable lines
+    #[default]
+    Human,
+    /// A single JSON object on stdout
+    Json,
+    /// A CSV header and one row on stdout, for spreadsheets
+    Csv,
+}
+
+/// Machine-readable run summary for `--output json|csv`
+#[derive(Debug, Serialize)]
+struct RunSummary {
+    total: usize,
+    successful: usize,
+    failed: usize,
+    parse_errors: usize,
+    /// NDJSON lines that parsed after trailing `\r`s or a BOM were removed
+    cleaned_lines: usize,
+    filtered_out: usize,
+    sampled_out: usize,
+    batches: usize,
+    duration_ms: u128,
+    /// Successfully sent events per second
+    throughput: f64,
+    interrupted: bool,
+    unsent: usize,
+    /// Stopped at the first failed batch, with --on-error abort or --fail-fast
+    aborted: bool,
+    /// Why a batch failed validation, which stopped the run after earlier batches
+    validation_error: Option<String>,
+    /// Events written to the --dead-letter file
+    dead_lettered: usize,
+    /// Events skipped because this --run-id already delivered them
+    already_sent: usize,
+    /// Events that failed --event-schema and were not sent
+    schema_rejected: usize,
+    /// SHA-256 of the raw input file; null when it wasn't read to the end or --file
+    /// was a pattern
+    input_sha256: Option<String>,
+    /// Events the gateway processed, with --await-processing
+    processed: Option<u64>,
+    /// Events the gateway rejected while processing, with --await-processing
+    rejected: Option<u64>,
+}
+
+impl RunSummary {
+    fn print(&self, format: SummaryFormat) -> Result<()> {
+        match format {
+            SummaryFormat::Human => {}
+            SummaryFormat::Json => println!("{}", serde_json::to_string_pretty(self)?),
+            SummaryFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(std::io::stdout());
+                writer.serialize(self)?;
+                writer.flush()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What `stream` does when a batch still fails after its retries
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OnError {
+    /// Count the batch as failed and keep sending
+    #[default]
+    Continue,
+    /// Stop sending, let batches in flight finish, and exit non-zero
+    Abort,
+}
+
+/// Transport for `stream`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Protocol {
+    /// Batched HTTP requests
+    Http,
+    /// A single WebSocket connection
+    Ws,
+}
+
+fn parse_key_value(s: &str) -> std::result::Result<(String, String), String> {
+    s.split_once('=')
+        .filter(|(key, _)| !key.is_empty())
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected key=value, got: {}", s))
+}
+
+/// Parse `Name: VAR` (or `Name: $VAR`) for --header-from-env
+fn parse_env_header(s: &str) -> std::result::Result<(String, String), String> {
+    let (name, var) = s
+        .split_once(':')
+        .ok_or_else(|| format!("expected 'Name: VAR', got: {}", s))?;
+    let (name, var) = (name.trim(), var.trim());
+    let var = var.strip_prefix('$').unwrap_or(var);
+    reqwest::header::HeaderName::from_bytes(name.as_bytes())
+        .map_err(|_| format!("invalid header name: {}", name))?;
+    if var.is_empty() {
+        return Err(format!("missing environment variable name in: {}", s));
+    }
+    Ok((name.to_string(), var.to_string()))
+}
+
+/// Merge run-level metadata into an event's `metadata` object, creating it if absent
+fn inject_metadata(event: &mut Value, meta: &[(String, String)], overwrite: bool) {
+    let Some(obj) = event.as_object_mut() else {
+        return;
+    };
+
+    let metadata = obj
+        .entry("metadata")
+        .or_insert_with(|| Value::Object(Default::default()));
+    if metadata.is_null() {
+        *metadata = Value::Object(Default::default());
+    }
+
+    let Some(metadata) = metadata.as_object_mut() else {
+        warn!("Event metadata is not an object; skipping --meta injection");
+        return;
+    };
+
+    for (key, value) in meta {
+        if overwrite || !metadata.contains_key(key) {
+            metadata.insert(key.clone(), Value::String(value.clone()));
+        }
+    }
+}
+
+/// Printed whenever --no-validate is used, so it stands out in CI logs
+pub fn warn_validation_skipped() {
+    println!(
+        "{} Client-side validation skipped (--no-validate); the server is the only check",
+        "⚠".yellow()
+    );
+}
+
+fn parse_rfc3339(s: &str) -> std::result::Result<DateTime<FixedOffset>, String> {
+    DateTime::parse_from_rfc3339(s).map_err(|e| format!("invalid RFC 3339 time '{}': {}", s, e))
+}
+
+/// Event time in epoch seconds, from a numeric or RFC 3339 `timestamp` field
+fn event_timestamp(event: &Value) -> Option<f64> {
+    match &event["timestamp"] {
+        Value::Number(secs) => secs.as_f64(),
+        Value::String(time) => DateTime::parse_from_rfc3339(time)
+            .ok()
+            .map(|t| t.timestamp_millis() as f64 / 1000.0),
+        _ => None,
+    }
+}
+
+/// Whether an event falls inside the --since/--until window
+fn in_time_window(event: &Value, args: &StreamArgs) -> bool {
+    if args.since.is_none() && args.until.is_none() {
+        return true;
+    }
+
+    let Some(timestamp) = event_timestamp(event) else {
+        return !args.require_timestamp;
+    };
+    let as_secs = |t: &DateTime<FixedOffset>| t.timestamp_millis() as f64 / 1000.0;
+
+    args.since.as_ref().is_none_or(|since| timestamp >= as_secs(since))
+        && args.until.as_ref().is_none_or(|until| timestamp < as_secs(until))
+}
+
+fn parse_sample_rate(s: &str) -> std::result::Result<f64, String> {
+    s.parse::<f64>()
+        .ok()
+        .filter(|rate| (0.0..=1.0).contains(rate))
+        .ok_or_else(|| format!("sample rate must be between 0.0 and 1.0, got: {}", s))
+}
+
+/// Deterministic sampling: the same event is always kept or dropped for a given rate
+fn sampled_in(event: &Value, rate: f64) -> bool {
+    (content_hash(event) as f64 / u64::MAX as f64) < rate
+}
+
+/// FNV-1a of the serialized event, stable across runs and Rust versions unlike
+/// `DefaultHasher`
+fn content_hash(event: &Value) -> u64 {
+    event.to_string().bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Threshold of unparseable lines tolerated before a stream is aborted
+#[derive(Debug, Clone, Copy)]
+pub enum ParseErrorLimit {
+    Count(usize),
+    Percent(f64),
+}
+
+impl ParseErrorLimit {
+    fn exceeded(&self, parse_errors: usize, total_lines: usize) -> bool {
+        match *self {
+            ParseErrorLimit::Count(max) => parse_errors > max,
+            ParseErrorLimit::Percent(max) => {
+                total_lines > 0 && (parse_errors as f64 / total_lines as f64) * 100.0 > max
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for ParseErrorLimit {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.strip_suffix('%') {
+            Some(percent) => percent
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|p| (0.0..=100.0).contains(p))
+                .map(ParseErrorLimit::Percent)
+                .ok_or_else(|| format!("invalid percentage: {}", s)),
+            None => s
+                .parse::<usize>()
+                .map(ParseErrorLimit::Count)
+                .map_err(|_| format!("expected a count or percentage, got: {}", s)),
+        }
+    }
+}
+
+/// Arguments for `pynthora-terminal stream replay`
+#[derive(Debug, Args)]
+pub struct ReplayArgs {
+    /// Dead-letter file written by `stream --dead-letter`
+    #[arg(short, long)]
+    pub file: String,
+    /// Pipeline ID for events that don't carry their own routing
+    #[arg(short, long)]
+    pub pipeline: Option<String>,
+    /// Route each event to the pipeline named in this field, as in the original run
+    #[arg(long)]
+    pub pipeline_field: Option<String>,
+    /// Send to this workspace instead of the configured one
+    #[arg(long)]
+    pub target_workspace: Option<String>,
+    /// Number of batches to send concurrently
+    #[arg(long, default_value_t = 1)]
+    pub concurrency: usize,
+    /// Write events that fail again to this file (must differ from --file)
+    #[arg(long)]
+    pub dead_letter: Option<String>,
+    /// Don't refuse to run against gateways older than `min_server_version`
+    #[arg(long)]
+    pub skip_version_check: bool,
+}
+
+/// Re-send a dead-letter file through the normal batching path.
+///
+/// `_error` tags are stripped, and every event gets a `metadata.idempotency_key`
+/// (kept if present, otherwise derived from its content) so replaying the same
+/// file twice doesn't duplicate events on the server.
+#[instrument(skip_all, fields(file = %args.file))]
+pub async fn replay(args: &ReplayArgs) -> Result<()> {
+    let config = Config::load(None)?;
+    let limits = config.batch_limits.clone().unwrap_or_default();
+    let mut client = Client::new(config)?;
+    if args.skip_version_check {
+        client = client.without_version_check();
+    }
+    client.check_server_version("stream replay").await?;
+
+    if let Some(path) = &args.dead_letter {
+        let same_file = std::fs::canonicalize(path)
+            .ok()
+            .zip(std::fs::canonicalize(&args.file).ok())
+            .is_some_and(|(a, b)| a == b);
+        if same_file {
+            anyhow::bail!("--dead-letter must not be the file being replayed");
+        }
+    }
+    let dead_letter = args
+        .dead_letter
+        .as_deref()
+        .map(|path| {
+            DeadLetter::open(path)
+                .with_context(|| format!("Failed to open dead-letter file: {}", path))
+        })
+        .transpose()?
+        .map(Arc::new);
+
+    println!("{} Replaying events from {}...", "ℹ".blue(), args.file);
+    let reader = open_with_retry(&args.file, Some(InputFormat::Ndjson)).await?;
+
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(
+        ProgressStyle::default_spinner()
+            .template("{spinner:.green} [{elapsed_precise}] {pos} events {msg}")
+            .unwrap(),
+    );
+    pb.set_message("Replaying...");
+
+    let parse_errors = AtomicUsize::new(0);
+    let read_error = Mutex::new(None);
+    let items = reader.map_while(|item| match item {
+        Err(Error::Io(e)) => {
+            *read_error.lock().unwrap() = Some(e);
+            None
+        }
+        item => Some(item),
+    });
+    let events = stream::iter(items).filter_map(|item| {
+        let event = match item {
+            Ok(event) => {
+                let mut event = strip_error(event);
+                ensure_idempotency_key(&mut event);
+                Some(event)
+            }
+            Err(e) => {
+                warn!("{}", e);
+                parse_errors.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        };
+        future::ready(event)
+    });
+
+    let opts = StreamOptions {
+        pipeline: args.pipeline.clone(),
+        pipeline_field: args.pipeline_field.clone(),
+        workspace: args.target_workspace.clone(),
+        batch_size: batch_size_for(&limits),
+        concurrency: args.concurrency,
+        max_in_flight_bytes: None,
+        retry: retry_config(),
+        // These events were already validated (or deliberately not) on the first run
+        validate: false,
+        limits: BatchLimits::default(),
+        progress: Arc::new(BarReporter { pb: pb.clone() }),
+        shutdown: Some(shutdown_on_ctrl_c(pb.clone())),
+        drain_timeout: SHUTDOWN_DRAIN_TIMEOUT,
+        abort_on_failure: false,
+        dead_letter: dead_letter.clone(),
+        sent_log: None,
+        journal: None,
+        adaptive: None,
+    };
+
+    let stats = client.stream_all(events, opts).await;
+    pb.finish_with_message("Complete");
+    if let Some(e) = read_error.into_inner().unwrap() {
+        return Err(e).context("Failed to read file");
+    }
+    let stats = stats?;
+
+    let parse_errors = parse_errors.into_inner();
+    if parse_errors > 0 {
+        println!("{} {} lines failed to parse", "⚠".yellow(), parse_errors);
+    }
+    if stats.successful > 0 {
+        println!("{} Replayed {} events successfully!", "✓".green(), stats.successful);
+    }
+    if stats.failed > 0 {
+        println!("{} {} events failed again", "✗".red(), stats.failed);
+    }
+    if stats.unsent > 0 {
+        println!(
+            "{} {} events were still in flight and may not have been delivered",
+            "⚠".yellow(),
+            stats.unsent
+        );
+    }
+    report_dead_letter(dead_letter.as_deref());
+
+    Ok(())
+}
 
-pub async fn run(file: &str, pipeline: Option<&str>) -> Result<()> {
+/// Give an event a stable `metadata.idempotency_key` so the server can drop repeats
+fn ensure_idempotency_key(event: &mut Value) {
+    let key = format!("{:016x}", content_hash(event));
+    let Some(obj) = event.as_object_mut() else {
+        return;
+    };
+
+    let metadata = obj
+        .entry("metadata")
+        .or_insert_with(|| Value::Object(Default::default()));
+    if let Some(metadata) = metadata.as_object_mut() {
+        metadata
+            .entry("idempotency_key")
+            .or_insert(Value::String(key));
+    }
+}
+
+#[instrument(skip_all, fields(file = %args.file, correlation_id = field::Empty))]
+pub async fn run(args: &StreamArgs) -> Result<()> {
+    if args.retry_max_delay < args.retry_initial_delay {
+        anyhow::bail!("--retry-max-delay must be at least --retry-initial-delay");
+    }
+
+    // Tags every log line of this run and every request it makes, so concurrent runs
+    // can be told apart here and on the gateway
+    let correlation_id = format!("{:016x}", rand::random::<u64>());
+    Span::current().record("correlation_id", correlation_id.as_str());
+
+    // Refuse projections that would strip required fields before touching the network
+    let projection = Projection::new(&args.drop_fields, &args.keep_only)?;
+    let schema = args
+        .event_schema
+        .as_deref()
+        .map(EventSchema::from_file)
+        .transpose()?;
     let config = Config::load(None)?;
-    let client = Client::new(config);
+    let mut client = Client::new(config)?;
+    client = client.with_correlation_id(correlation_id);
+    for (name, var) in &args.env_headers {
+        client = client.with_env_header(name, var);
+    }
+    client.check_env_headers()?;
+    if let Some(level) = compression_level(args) {
+        client = client.with_compression(level);
+    }
+    client = client.with_encoding(args.encoding);
+    if args.print_request {
+        client = client.with_request_printing();
+    }
+    if args.skip_version_check {
+        client = client.without_version_check();
+    }
+    client.check_server_version("stream").await?;
+    let file = args.file.as_str();
+    let files = expand_pattern(file)?;
+    let limits = config.batch_limits.clone().unwrap_or_default();
+
+    if args.no_validate {
+        warn_validation_skipped();
+    }
 
-    println!("{} Reading data from {}...", "ℹ".blue(), file);
+    let dead_letter = args
+        .dead_letter
+        .as_deref()
+        .map(|path| {
+            DeadLetter::open(path)
+                .with_context(|| format!("Failed to open dead-letter file: {}", path))
+        })
+        .transpose()?
+        .map(Arc::new);
+
+    let journal = args
+        .journal
+        .as_deref()
+        .map(|path| {
+            Journal::open(path).with_context(|| format!("Failed to open journal: {}", path))
+        })
+        .transpose()?
+        .map(Arc::new);
 
-    let file_handle = File::open(file)
-        .with_context(|| format!("Failed to open file: {}", file))?;
+    let sent_log = args
+        .run_id
+        .as_deref()
+        .map(SentLog::for_run)
+        .transpose()?
+        .map(Arc::new);
 
-    let reader = BufReader::new(file_handle);
-    let lines: Vec<String> = reader
-        .lines()
-        .collect::<Result<Vec<_>, _>>()
-        .context("Failed to read file")?;
+    let signing_key = if args.sign {
+        let path = args
+            .signing_key
+            .as_deref()
+            .or(config.signing_key_path.as_deref())
+            .ok_or_else(|| {
+                Error::Config(
+                    "--sign needs --signing-key or signing_key_path in the config".to_string(),
+                )
+            })?;
+        Some(load_signing_key(path)?)
+    } else {
+        None
+    };
+
+    if args.follow {
+        if args.protocol == Protocol::Ws {
+            anyhow::bail!("--follow is only supported with --protocol http");
+        }
+        if files != [file] {
+            anyhow::bail!("--follow needs a single file, not a pattern");
+        }
+        let signing_key = signing_key.as_ref();
+        let sinks = (dead_letter.as_deref(), journal.as_deref());
+        return follow(&client, args, &limits, &projection, sinks, signing_key).await;
+    }
+
+    // Machine-readable summaries keep stdout free of progress lines
+    let human = args.output == SummaryFormat::Human;
+    if human && files != [file] {
+        println!("{} Reading data from {} files matching {}...", "ℹ".blue(), files.len(), file);
+    } else if human {
+        println!("{} Reading data from {}...", "ℹ".blue(), file);
+    }
 
-    let total_lines = lines.len();
-    let pb = ProgressBar::new(total_lines as u64);
+    // Thresholds must hold before anything is sent, so check them in one cheap first
+    // pass; it reads the whole file only when a count is needed
+    let counts_needed = args.max_parse_errors.is_some() || args.check_quota;
+    let scan = scan_input(args, &files, &projection, counts_needed).await?;
+    if let Some(limit) = args.max_parse_errors {
+        let non_empty = scan.parsed + scan.parse_errors;
+        if limit.exceeded(scan.parse_errors, non_empty) {
+            return Err(Error::Validation(format!(
+                "{} of {} lines failed to parse, exceeding --max-parse-errors; nothing was sent",
+                scan.parse_errors, non_empty
+            ))
+            .into());
+        }
+    }
+    if scan.parsed == 0 {
+        let detail = if scan.parse_errors > 0 {
+            format!(" ({} lines failed to parse)", scan.parse_errors)
+        } else {
+            String::new()
+        };
+        return Err(Error::NoEvents(format!("{}{}", file, detail)).into());
+    }
+    if args.validate_sample.is_some() {
+        if !scan.sample_errors.is_empty() {
+            return Err(Error::Validation(format!(
+                "{} errors in the first {} events (--validate-sample); nothing was sent: {}",
+                scan.sample_errors.len(),
+                scan.sampled,
+                scan.sample_errors.join("; ")
+            ))
+            .into());
+        }
+        if human {
+            println!("{} First {} events passed validation", "✓".green(), scan.sampled);
+        }
+    }
+    if args.check_quota {
+        check_quota(&client, scan.parsed, human).await;
+    }
+    let pb = ProgressBar::new_spinner();
     pb.set_style(
-        ProgressStyle::default_bar()
-            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} {msg}")
-            .unwrap()
-            .progress_chars("#>-"),
+        ProgressStyle::default_spinner()
+            .template("{spinner:.green} [{elapsed_precise}] {pos} events {msg}")
+            .unwrap(),
     );
     pb.set_message("Streaming data...");
 
-    // Parse all events first
-    let mut events = Vec::new();
-    let mut parse_errors = 0;
+    // Events are read, parsed and transformed lazily so memory is bounded by the batch size
+    let counts = InputCounts::default();
+    let source = EventSource {
+        args,
+        projection: &projection,
+        schema: schema.as_ref(),
+        dead_letter: dead_letter.as_deref(),
+        signing_key: signing_key.as_ref(),
+        sent_log: sent_log.as_deref(),
+        counts: &counts,
+    };
+
+    if args.protocol == Protocol::Ws {
+        if args.pipeline_field.is_some() {
+            anyhow::bail!("--pipeline-field is only supported with --protocol http");
+        }
+        if args.encoding != Encoding::Json {
+            anyhow::bail!("--encoding is only supported with --protocol http");
+        }
+        if !human {
+            anyhow::bail!("--output json/csv is only supported with --protocol http");
+        }
+        if args.await_processing {
+            anyhow::bail!("--await-processing is only supported with --protocol http");
+        }
+        if args.on_error == OnError::Abort {
+            anyhow::bail!("--on-error abort is only supported with --protocol http");
+        }
+        if args.fail_fast {
+            anyhow::bail!("--fail-fast is only supported with --protocol http");
+        }
 
-    for (idx, line) in lines.iter().enumerate() {
-        if line.trim().is_empty() {
-            continue;
+        // WebSocket sends track acks across the whole run, so the events are buffered
+        let events: Vec<Value> = source.events(&files).collect().await;
+        if let Some(e) = counts.read_error.lock().unwrap().take() {
+            return Err(e);
+        }
+        if let Some(e) = counts.sign_error.lock().unwrap().take() {
+            return Err(Error::Validation(format!("{:#}", e)).into());
         }
+        report_parse_results(
+            args,
+            &counts.parse_errors,
+            &counts.sampled_out,
+            &counts.filtered_out,
+        );
+        report_cleaned_lines(counts.cleaned_lines());
+        report_schema_rejected(&counts.schema_rejected, dead_letter.as_deref());
+        report_already_sent(sent_log.as_deref(), &counts.already_sent);
 
-        match serde_json::from_str::<Value>(line) {
-            Ok(event) => events.push(event),
-            Err(e) => {
-                warn!("Failed to parse line {}: {}", idx + 1, e);
-                parse_errors += 1;
+        if !args.no_validate {
+            let validation = validate_batch(&events, &limits);
+            if !validation.is_valid {
+                return Err(Error::Validation(validation.errors.join("; ")).into());
+            }
+        }
+
+        pb.set_message("Streaming over WebSocket...");
+        let pipeline = args.pipeline.as_deref().or(config.default_pipeline.as_deref());
+        let report = WebSocketClient::new(config.clone())
+            .send_events(&events, pipeline, args.target_workspace.as_deref())
+            .await?;
+        pb.set_position(events.len() as u64);
+        pb.finish_with_message("Complete");
+
+        if report.acked() > 0 {
+            println!(
+                "{} Streamed {} events over WebSocket!",
+                "✓".green(),
+                report.acked()
+            );
+        }
+        if report.rejected() > 0 {
+            println!("{} {} events rejected by the server", "✗".red(), report.rejected());
+        }
+        if report.unacked() > 0 {
+            println!("{} {} events were not acknowledged", "⚠".yellow(), report.unacked());
+        }
+        history::record_counts(serde_json::json!({
+            "total": events.len(),
+            "successful": report.acked(),
+            "failed": report.rejected() + report.unacked(),
+        }));
+        let acked: Vec<Value> = events
+            .iter()
+            .zip(&report.outcomes)
+            .filter(|(_, outcome)| matches!(outcome, Delivery::Acked))
+            .map(|(event, _)| event.clone())
+            .collect();
+        if let Some(sent_log) = &sent_log {
+            sent_log.record(&acked)?;
+        }
+        if let Some(journal) = &journal {
+            journal.record(&acked)?;
+            report_journal(Some(journal));
+        }
+        if let Some(dead_letter) = &dead_letter {
+            for index in report.failed_indices() {
+                let reason = match &report.outcomes[index] {
+                    Delivery::Rejected(reason) => reason.as_str(),
+                    _ => "not acknowledged",
+                };
+                dead_letter.write(std::slice::from_ref(&events[index]), reason)?;
+            }
+            report_dead_letter(Some(dead_letter));
+        }
+        counts.log_digests();
+        report_input_digests(&counts, files.len());
+        return Ok(());
+    }
+
+    // Adaptive batches never outgrow a configured hard cap
+    let adaptive = args
+        .adaptive
+        .then(|| AdaptiveConfig::default().capped(limits.max_events));
+
+    // Each batch is validated against the configured (or default) limits as it is formed
+    let opts = StreamOptions {
+        pipeline: args.pipeline.clone(),
+        pipeline_field: args.pipeline_field.clone(),
+        workspace: args.target_workspace.clone(),
+        batch_size: batch_size_for(&limits),
+        concurrency: args.concurrency,
+        max_in_flight_bytes: args.max_in_flight_bytes,
+        retry: retry_config_for(args),
+        validate: !args.no_validate,
+        limits,
+        progress: Arc::new(BarReporter { pb: pb.clone() }),
+        shutdown: Some(shutdown_on_ctrl_c(pb.clone())),
+        drain_timeout: SHUTDOWN_DRAIN_TIMEOUT,
+        abort_on_failure: args.on_error == OnError::Abort || args.fail_fast,
+        dead_letter: dead_letter.clone(),
+        sent_log: sent_log.clone(),
+        journal: journal.clone(),
+        adaptive,
+    };
+
+    let stats = if args.fail_fast {
+        stream_each_file(&client, &source, &files, opts).await
+    } else {
+        client.stream_all(source.events(&files), opts).await
+    };
+    pb.finish_with_message(match &stats {
+        Ok(stats) if stats.interrupted => "Interrupted",
+        Ok(stats) if stats.aborted => "Aborted",
+        Ok(stats) if stats.validation_error.is_some() => "Invalid batch",
+        _ => "Complete",
+    });
+    counts.log_digests();
+
+    if let Some(e) = counts.read_error.lock().unwrap().take() {
+        return Err(e);
+    }
+    if human {
+        report_parse_results(
+            args,
+            &counts.parse_errors,
+            &counts.sampled_out,
+            &counts.filtered_out,
+        );
+        report_cleaned_lines(counts.cleaned_lines());
+        report_schema_rejected(&counts.schema_rejected, dead_letter.as_deref());
+        report_already_sent(sent_log.as_deref(), &counts.already_sent);
+    }
+    let stats = stats?;
+    history::record_counts(serde_json::json!({
+        "total": stats.total,
+        "successful": stats.successful,
+        "failed": stats.failed,
+        "parse_errors": counts.parse_errors.load(Ordering::Relaxed),
+    }));
+    if let Some(e) = counts.sign_error.lock().unwrap().take() {
+        if human {
+            println!(
+                "{} Stopped after {} events: an event could not be signed",
+                "✗".red(),
+                stats.successful + stats.failed
+            );
+        }
+        return Err(Error::Validation(format!("{:#}", e)).into());
+    }
+
+    if !human {
+        let processing = if args.await_processing {
+            Some(await_processing(&client, &stats.batch_ids, &retry_config_for(args)).await?)
+        } else {
+            None
+        };
+        let seconds = stats.elapsed.as_secs_f64();
+        let summary = RunSummary {
+            total: stats.total,
+            successful: stats.successful,
+            failed: stats.failed,
+            parse_errors: counts.parse_errors.load(Ordering::Relaxed),
+            cleaned_lines: counts.cleaned_lines(),
+            filtered_out: counts.filtered_out.load(Ordering::Relaxed),
+            sampled_out: counts.sampled_out.load(Ordering::Relaxed),
+            batches: stats.batches,
+            duration_ms: stats.elapsed.as_millis(),
+            throughput: if seconds > 0.0 {
+                stats.successful as f64 / seconds
+            } else {
+                0.0
+            },
+            interrupted: stats.interrupted,
+            unsent: stats.unsent,
+            aborted: stats.aborted,
+            validation_error: stats.validation_error.clone(),
+            dead_lettered: dead_letter.as_ref().map_or(0, |d| d.written()),
+            already_sent: counts.already_sent.load(Ordering::Relaxed),
+            schema_rejected: counts.schema_rejected.load(Ordering::Relaxed),
+            input_sha256: counts.sha256(files.len()),
+            processed: processing.as_ref().map(|report| report.processed),
+            rejected: processing.as_ref().map(|report| report.rejected),
+        };
+        summary.print(args.output)?;
+        return ensure_not_aborted(&stats);
+    }
+
+    if stats.successful > 0 {
+        println!(
+            "{} Streamed {} events successfully!",
+            "✓".green(),
+            stats.successful
+        );
+    }
+
+    if stats.failed > 0 {
+        println!("{} {} events failed to stream", "✗".red(), stats.failed);
+    }
+
+    if stats.interrupted {
+        println!(
+            "{} Interrupted: stopped reading after {} events",
+            "⚠".yellow(),
+            stats.total
+        );
+        if stats.unsent > 0 {
+            println!(
+                "{} {} events were still in flight and may not have been delivered",
+                "⚠".yellow(),
+                stats.unsent
+            );
+        }
+    }
+
+    if stats.aborted {
+        println!(
+            "{} Aborted after a batch failed: stopped reading after {} events",
+            "✗".red(),
+            stats.total
+        );
+    }
+
+    if let Some(error) = &stats.validation_error {
+        println!(
+            "{} Stopped at a batch that failed validation after reading {} events: {}",
+            "✗".red(),
+            stats.total,
+            error
+        );
+    }
+
+    report_dead_letter(dead_letter.as_deref());
+    report_journal(journal.as_deref());
+    report_input_digests(&counts, files.len());
+    ensure_not_aborted(&stats)?;
+
+    if args.await_processing {
+        if stats.batch_ids.is_empty() {
+            println!(
+                "{} The gateway returned no batch ids, so there is no processing to wait for",
+                "⚠".yellow()
+            );
+        } else {
+            println!(
+                "{} Waiting for the gateway to process {} batches...",
+                "ℹ".blue(),
+                stats.batch_ids.len()
+            );
+            await_processing(&client, &stats.batch_ids, &retry_config_for(args))
+                .await?
+                .print();
+        }
+    }
+
+    if args.adaptive {
+        println!(
+            "{} Adaptive: settled on concurrency {} with batches of {} events",
+            "ℹ".blue(),
+            stats.final_concurrency,
+            stats.final_batch_size
+        );
+    }
+
+    if args.verbose && client.compression_enabled() {
+        print_compression_summary(&client);
+    }
+
+    Ok(())
+}
+
+/// Send each input file on its own, so no batch spans two files, stopping after the
+/// first one with a failed batch (--fail-fast)
+async fn stream_each_file(
+    client: &Client,
+    source: &EventSource<'_>,
+    files: &[String],
+    opts: StreamOptions,
+) -> pynthora_terminal::core::error::Result<StreamStats> {
+    let mut stats = StreamStats::default();
+    for file in files {
+        let events = source.events(std::slice::from_ref(file));
+        stats.merge(client.stream_all(events, opts.clone()).await?);
+        if stats.failed > 0 {
+            stats.aborted = true;
+        }
+        if stats.aborted || stats.interrupted || stats.validation_error.is_some() {
+            break;
+        }
+        if source.counts.stopped() {
+            break;
+        }
+    }
+    Ok(stats)
+}
+
+/// Turns input files into the events to send: parsed, filtered, projected, checked
+/// against the schema, signed and deduplicated, as they are read
+struct EventSource<'a> {
+    args: &'a StreamArgs,
+    projection: &'a Projection,
+    schema: Option<&'a EventSchema>,
+    dead_letter: Option<&'a DeadLetter>,
+    signing_key: Option<&'a SigningKey>,
+    sent_log: Option<&'a SentLog>,
+    counts: &'a InputCounts,
+}
+
+/// What an [`EventSource`] read and dropped, across every file it was given
+#[derive(Default)]
+struct InputCounts {
+    parse_errors: AtomicUsize,
+    sampled_out: AtomicUsize,
+    filtered_out: AtomicUsize,
+    schema_rejected: AtomicUsize,
+    already_sent: AtomicUsize,
+    signed: AtomicUsize,
+    /// A file that couldn't be opened or read, which ends the input
+    read_error: Mutex<Option<anyhow::Error>>,
+    /// An event that couldn't be signed, which ends the input
+    sign_error: Mutex<Option<anyhow::Error>>,
+    /// Every file opened so far, in order
+    opened: Mutex<Vec<OpenedInput>>,
+}
+
+struct OpenedInput {
+    path: String,
+    digest: InputDigest,
+    cleaned: CleanedLines,
+}
+
+impl InputCounts {
+    /// Whether reading ended early on a read or signing error
+    fn stopped(&self) -> bool {
+        self.read_error.lock().unwrap().is_some() || self.sign_error.lock().unwrap().is_some()
+    }
+
+    fn cleaned_lines(&self) -> usize {
+        let opened = self.opened.lock().unwrap();
+        opened.iter().map(|input| input.cleaned.count()).sum()
+    }
+
+    /// SHA-256 of the input when it is a single file read to the end
+    fn sha256(&self, files: usize) -> Option<String> {
+        match self.opened.lock().unwrap().as_slice() {
+            [only] if files == 1 => only.digest.sha256(),
+            _ => None,
+        }
+    }
+
+    fn log_digests(&self) {
+        for input in self.opened.lock().unwrap().iter() {
+            log_input_digest(&input.path, &input.digest);
+        }
+    }
+}
+
+impl EventSource<'_> {
+    /// Events from `files`, read in order as one stream with each file opened when the
+    /// previous one runs out. Stops at the first file that can't be opened or read
+    fn events<'s>(&'s self, files: &'s [String]) -> impl Stream<Item = Value> + 's {
+        let args = self.args;
+        let counts = self.counts;
+
+        let readers = stream::iter(files)
+            .then(move |file| async move {
+                let opened = retry_open(file, || {
+                    EventReader::open_hashed(file, args.input_format, args.input_encoding)
+                })
+                .await;
+                (file, opened)
+            })
+            .scan((), move |_, (file, opened)| {
+                let reader = match opened {
+                    Ok((reader, digest)) => {
+                        counts.opened.lock().unwrap().push(OpenedInput {
+                            path: file.clone(),
+                            digest,
+                            cleaned: reader.cleaned_lines(),
+                        });
+                        Some(stream::iter(reader))
+                    }
+                    Err(e) => {
+                        *counts.read_error.lock().unwrap() = Some(e);
+                        None
+                    }
+                };
+                future::ready(reader)
+            });
+
+        // Stop at the first IO error; events that fail to decode are counted and skipped
+        let items = readers.flatten().scan((), move |_, item| {
+            future::ready(match item {
+                Err(Error::Io(e)) => {
+                    let e = anyhow::Error::new(e).context("Failed to read file");
+                    *counts.read_error.lock().unwrap() = Some(e);
+                    None
+                }
+                item => Some(item),
+            })
+        });
+
+        let events = items.enumerate().filter_map(move |(index, item)| {
+            let event = match item {
+                Err(e) => {
+                    warn!("{}", e);
+                    counts.parse_errors.fetch_add(1, Ordering::Relaxed);
+                    None
+                }
+                Ok(event) if !in_time_window(&event, args) => {
+                    counts.filtered_out.fetch_add(1, Ordering::Relaxed);
+                    None
+                }
+                Ok(event) if args.sample.is_some_and(|rate| !sampled_in(&event, rate)) => {
+                    counts.sampled_out.fetch_add(1, Ordering::Relaxed);
+                    None
+                }
+                Ok(mut event) => {
+                    self.projection.apply(&mut event);
+                    if !args.meta.is_empty() {
+                        inject_metadata(&mut event, &args.meta, args.meta_override);
+                    }
+                    match self.schema.map(|schema| schema.violations(&event)) {
+                        Some(violations) if !violations.is_empty() => {
+                            let error = format!("schema violation {}", violations.join("; "));
+                            warn!("Event {}: {}", index, error);
+                            counts.schema_rejected.fetch_add(1, Ordering::Relaxed);
+                            write_dead_letter(self.dead_letter, &[event], &error);
+                            None
+                        }
+                        _ => Some(event),
+                    }
+                }
+            };
+            future::ready(event)
+        });
+
+        // Signing failures end the input so nothing unsigned is sent after them
+        let events = events.scan((), move |_, mut event| {
+            if let Some(key) = self.signing_key {
+                let signed = counts.signed.fetch_add(1, Ordering::Relaxed) + 1;
+                if let Err(e) = sign_value(&mut event, key) {
+                    let e = e.context(format!("Event {}", signed));
+                    *counts.sign_error.lock().unwrap() = Some(e);
+                    return future::ready(None);
+                }
+            }
+            future::ready(Some(event))
+        });
+
+        // Keys are derived last, from the event exactly as it will be sent
+        events.filter_map(move |mut event| {
+            if let Some(sent_log) = self.sent_log {
+                ensure_idempotency_key(&mut event);
+                if sent_log.contains(&event) {
+                    counts.already_sent.fetch_add(1, Ordering::Relaxed);
+                    return future::ready(None);
+                }
+            }
+            future::ready(Some(event))
+        })
+    }
+}
+
+/// Fail the run when it stopped at a failed or invalid batch
+fn ensure_not_aborted(stats: &StreamStats) -> Result<()> {
+    if let Some(error) = &stats.validation_error {
+        return Err(Error::Validation(error.clone()).into());
+    }
+    if stats.aborted {
+        anyhow::bail!(
+            "stopped after a batch failed; {} events failed and the rest of the input was not sent",
+            stats.failed
+        );
+    }
+    Ok(())
+}
+
+/// Gateway-side outcome of a run, from --await-processing
+#[derive(Debug, Default)]
+struct ProcessingReport {
+    processed: u64,
+    rejected: u64,
+    /// Rejected events with the id of their batch
+    rejections: Vec<(String, RejectedEvent)>,
+    /// Batches still being processed when `AWAIT_PROCESSING_TIMEOUT` ran out
+    unfinished: usize,
+}
+
+impl ProcessingReport {
+    fn print(&self) {
+        let icon = if self.rejected == 0 {
+            "✓".green()
+        } else {
+            "⚠".yellow()
+        };
+        println!(
+            "{} Gateway processed {} events, rejected {}",
+            icon, self.processed, self.rejected
+        );
+
+        for (batch_id, rejection) in self.rejections.iter().take(MAX_REJECTIONS_SHOWN) {
+            match rejection.index {
+                Some(index) => {
+                    println!("  - batch {} event {}: {}", batch_id, index, rejection.error)
+                }
+                None => println!("  - batch {}: {}", batch_id, rejection.error),
+            }
+        }
+        if self.rejections.len() > MAX_REJECTIONS_SHOWN {
+            println!("  ... and {} more", self.rejections.len() - MAX_REJECTIONS_SHOWN);
+        }
+
+        if self.unfinished > 0 {
+            println!(
+                "{} {} batches were still processing after {:?}",
+                "⚠".yellow(),
+                self.unfinished,
+                AWAIT_PROCESSING_TIMEOUT
+            );
+        }
+    }
+}
+
+/// Poll each batch until the gateway reports it done, or `AWAIT_PROCESSING_TIMEOUT`
+/// passes, adding up processed and rejected events
+async fn await_processing(
+    client: &Client,
+    batch_ids: &[String],
+    retry: &RetryConfig,
+) -> Result<ProcessingReport> {
+    let deadline = Instant::now() + AWAIT_PROCESSING_TIMEOUT;
+    let mut report = ProcessingReport::default();
+    let mut pending: Vec<&str> = batch_ids.iter().map(String::as_str).collect();
+
+    while !pending.is_empty() {
+        let statuses: Vec<_> = stream::iter(&pending)
+            .map(|id| retry_with_backoff(retry, move || client.get_batch_status(id)))
+            .buffered(BATCH_POLL_CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut unfinished = Vec::new();
+        for (id, status) in pending.into_iter().zip(statuses) {
+            let status = status.with_context(|| format!("Failed to fetch status of batch {}", id))?;
+            if !status.is_terminal() {
+                unfinished.push(id);
+                continue;
+            }
+            report.processed += status.processed;
+            report.rejected += status.rejected;
+            report
+                .rejections
+                .extend(status.errors.into_iter().map(|error| (id.to_string(), error)));
+        }
+
+        pending = unfinished;
+        if !pending.is_empty() {
+            if Instant::now() >= deadline {
+                report.unfinished = pending.len();
+                break;
+            }
+            sleep(BATCH_POLL_INTERVAL).await;
+        }
+    }
+
+    Ok(report)
+}
+
+/// A shutdown signal for `stream_all` that fires on the first Ctrl+C; a second
+/// Ctrl+C exits immediately
+pub fn shutdown_on_ctrl_c(pb: ProgressBar) -> watch::Receiver<bool> {
+    let (tx, rx) = watch::channel(false);
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_err() {
+            return;
+        }
+        pb.set_message("Interrupted, finishing in-flight batches (Ctrl+C again to abort)...");
+        let _ = tx.send(true);
+
+        if tokio::signal::ctrl_c().await.is_ok() {
+            pb.abandon_with_message("Aborted");
+            std::process::exit(130);
+        }
+    });
+    rx
+}
+
+/// Record what exactly was ingested, for audit trails
+fn log_input_digest(file: &str, digest: &InputDigest) {
+    if let Some(sha256) = digest.sha256() {
+        info!(file, sha256 = sha256.as_str(), bytes = digest.bytes(), "Input digest");
+    }
+}
+
+/// The digest of a single input file, or a total for the files of a pattern, whose
+/// digests are only logged
+fn report_input_digests(counts: &InputCounts, files: usize) {
+    let opened = counts.opened.lock().unwrap();
+    match opened.as_slice() {
+        [only] if files == 1 => report_input_digest(&only.digest),
+        _ => println!(
+            "{} Read {} of {} files ({} bytes); set RUST_LOG=info for each file's SHA-256",
+            "ℹ".blue(),
+            opened.len(),
+            files,
+            opened.iter().map(|input| input.digest.bytes()).sum::<u64>()
+        ),
+    }
+}
+
+fn report_input_digest(digest: &InputDigest) {
+    match digest.sha256() {
+        Some(sha256) => {
+            println!("{} Input SHA-256: {} ({} bytes)", "ℹ".blue(), sha256, digest.bytes())
+        }
+        None => println!(
+            "{} The input was not read to the end, so no SHA-256 was recorded",
+            "⚠".yellow()
+        ),
+    }
+}
+
+fn report_journal(journal: Option<&Journal>) {
+    if let Some(journal) = journal.filter(|j| j.written() > 0) {
+        println!(
+            "{} {} delivered events journaled in {}",
+            "ℹ".blue(),
+            journal.written(),
+            journal.path().display()
+        );
+    }
+}
+
+fn report_dead_letter(dead_letter: Option<&DeadLetter>) {
+    if let Some(dead_letter) = dead_letter.filter(|d| d.written() > 0) {
+        println!(
+            "{} {} undelivered events written to {}",
+            "ℹ".blue(),
+            dead_letter.written(),
+            dead_letter.path().display()
+        );
+    }
+}
+
+/// Resolve --compress/--compression-level, clamping levels above the gzip maximum
+fn compression_level(args: &StreamArgs) -> Option<Compression> {
+    match args.compression_level {
+        Some(level) if level > 9 => {
+            println!(
+                "{} Compression level {} is out of range (0-9), using 9",
+                "⚠".yellow(),
+                level
+            );
+            Some(Compression::new(9))
+        }
+        Some(level) => Some(Compression::new(level)),
+        None if args.compress => Some(Compression::default()),
+        None => None,
+    }
+}
+
+fn print_compression_summary(client: &Client) {
+    let (raw, sent) = client.batch_bytes();
+    if raw == 0 {
+        return;
+    }
+
+    println!(
+        "{} Compression: {} → {} bytes ({:.1}x, saved {} bytes / {:.1}%)",
+        "ℹ".blue(),
+        raw,
+        sent,
+        raw as f64 / sent.max(1) as f64,
+        raw.saturating_sub(sent),
+        (1.0 - sent as f64 / raw as f64) * 100.0
+    );
+}
+
+/// What the first pass over the input found
+struct InputScan {
+    parsed: usize,
+    parse_errors: usize,
+    /// Events checked for `--validate-sample`
+    sampled: usize,
+    sample_errors: Vec<String>,
+}
+
+/// Read the input once before anything is sent: count parseable and unparseable
+/// events and validate the first `--validate-sample` events the way their batches
+/// will be. Without `count_all`, reading stops once an event has parsed and the
+/// sample is done, which is all the non-empty check needs
+async fn scan_input(
+    args: &StreamArgs,
+    files: &[String],
+    projection: &Projection,
+    count_all: bool,
+) -> Result<InputScan> {
+    let sample = args.validate_sample.unwrap_or(0);
+    let mut scan = InputScan {
+        parsed: 0,
+        parse_errors: 0,
+        sampled: 0,
+        sample_errors: Vec::new(),
+    };
+    for file in files {
+        for item in open_encoded_with_retry(file, args.input_format, args.input_encoding).await? {
+            let mut event = match item {
+                Ok(event) => event,
+                Err(Error::Io(e)) => return Err(e).context("Failed to read file"),
+                Err(_) => {
+                    scan.parse_errors += 1;
+                    continue;
+                }
+            };
+            scan.parsed += 1;
+            if scan.sampled < sample {
+                projection.apply(&mut event);
+                if !args.meta.is_empty() {
+                    inject_metadata(&mut event, &args.meta, args.meta_override);
+                }
+                for error in validate_event(&event).errors {
+                    scan.sample_errors.push(format!("Event {}: {}", scan.sampled, error));
+                }
+                scan.sampled += 1;
+            }
+            if !count_all && scan.sampled == sample {
+                return Ok(scan);
             }
         }
     }
+    Ok(scan)
+}
+
+/// Warn, without stopping, when `events` won't fit in the remaining quota or the
+/// quota can't be fetched
+async fn check_quota(client: &Client, events: usize, human: bool) {
+    let warning = match client.get_usage().await {
+        Ok(usage) => quota_warning(&usage, events),
+        Err(e) => Some(format!("Could not check the workspace quota: {}", e)),
+    };
+
+    match warning {
+        Some(warning) if human => println!("{} {}", "⚠".yellow(), warning),
+        Some(warning) => warn!("{}", warning),
+        None => debug!("{} events fit in the remaining quota", events),
+    }
+}
+
+/// Open an event file, retrying transient IO errors (EINTR, EAGAIN, stale NFS
+/// handles) so a flaky mount doesn't abort a long job
+pub async fn open_with_retry(file: &str, format: Option<InputFormat>) -> Result<EventReader> {
+    open_encoded_with_retry(file, format, InputEncoding::Utf8).await
+}
+
+/// [`open_with_retry`] for a file that may not be UTF-8
+pub async fn open_encoded_with_retry(
+    file: &str,
+    format: Option<InputFormat>,
+    encoding: InputEncoding,
+) -> Result<EventReader> {
+    retry_open(file, || EventReader::open_with_encoding(file, format, encoding)).await
+}
+
+/// Retry `open` on transient IO errors
+async fn retry_open<T>(
+    file: &str,
+    open: impl Fn() -> pynthora_terminal::core::error::Result<T>,
+) -> Result<T> {
+    let retry = RetryConfig {
+        max_attempts: 5,
+        initial_delay: Duration::from_millis(200),
+        max_delay: Duration::from_secs(2),
+        strategy: BackoffStrategy::default(),
+    };
+
+    retry_with_backoff_if(
+        &retry,
+        || async { open() },
+        |e| matches!(e, Error::Io(io) if is_retryable_io_error(io)),
+    )
+    .await
+    .with_context(|| format!("Failed to open file: {}", file))
+}
+
+fn report_cleaned_lines(count: usize) {
+    if count > 0 {
+        println!(
+            "{} {} lines had stray carriage returns or a byte order mark and were cleaned up",
+            "⚠".yellow(),
+            count
+        );
+    }
+}
 
+fn report_parse_results(
+    args: &StreamArgs,
+    parse_errors: &AtomicUsize,
+    sampled_out: &AtomicUsize,
+    filtered_out: &AtomicUsize,
+) {
+    let parse_errors = parse_errors.load(Ordering::Relaxed);
     if parse_errors > 0 {
         println!(
             "{} {} lines failed to parse",
@@ -65,73 +1595,372 @@ pub async fn run(file: &str, pipeline: Option<&str>) -> Result<()> {
         );
     }
 
-    // Validate batch
-    let validation = validate_batch(&events);
-    if !validation.is_valid {
-        println!("{} Validation errors found:", "⚠".yellow());
-        for error in &validation.errors {
-            println!("  - {}", error);
-        }
-        if !validation.errors.is_empty() {
-            anyhow::bail!("Batch validation failed");
-        }
+    if args.since.is_some() || args.until.is_some() {
+        println!(
+            "{} Time window: filtered out {} events",
+            "ℹ".blue(),
+            filtered_out.load(Ordering::Relaxed)
+        );
+    }
+
+    if let Some(rate) = args.sample {
+        println!(
+            "{} Sampling {:.1}%: sampled out {} events",
+            "ℹ".blue(),
+            rate * 100.0,
+            sampled_out.load(Ordering::Relaxed)
+        );
     }
+}
+
+fn report_schema_rejected(schema_rejected: &AtomicUsize, dead_letter: Option<&DeadLetter>) {
+    let schema_rejected = schema_rejected.load(Ordering::Relaxed);
+    if schema_rejected > 0 {
+        let kept = match dead_letter {
+            Some(dead_letter) => format!(", written to {}", dead_letter.path().display()),
+            None => String::new(),
+        };
+        println!(
+            "{} {} events failed schema validation and were not sent{}",
+            "✗".red(),
+            schema_rejected,
+            kept
+        );
+    }
+}
 
-    if !validation.warnings.is_empty() {
-        for warning in &validation.warnings {
-            println!("{} {}", "⚠".yellow(), warning);
+fn report_already_sent(sent_log: Option<&SentLog>, already_sent: &AtomicUsize) {
+    let already_sent = already_sent.load(Ordering::Relaxed);
+    if already_sent > 0 {
+        if let Some(sent_log) = sent_log {
+            println!(
+                "{} Skipped {} events already sent under this run id ({})",
+                "ℹ".blue(),
+                already_sent,
+                sent_log.path().display()
+            );
         }
     }
+}
+
+/// Drives the CLI progress bar from stream engine callbacks
+struct BarReporter {
+    pb: ProgressBar,
+}
+
+impl ProgressReporter for BarReporter {
+    fn on_batch_complete(&self, done: usize, _total: usize) {
+        self.pb.set_position(done as u64);
+        self.pb.set_message("Streaming data...");
+    }
+
+    fn on_error(&self, batch_num: usize, error: &str) {
+        self.pb.set_message(format!("Batch {} failed: {}", batch_num, error));
+    }
+
+    fn on_retry(&self, batch_num: usize, attempt: u32, max_attempts: u32) {
+        self.pb.set_message(format!(
+            "Batch {} (retry {}/{})...",
+            batch_num, attempt, max_attempts
+        ));
+    }
+}
+
+/// Events per batch: the default, or fewer when the config caps batches with
+/// `batch_limits.max_events`
+pub fn batch_size_for(limits: &BatchLimits) -> usize {
+    limits
+        .max_events
+        .map_or(DEFAULT_BATCH_SIZE, |max| DEFAULT_BATCH_SIZE.min(max))
+        .max(1)
+}
+
+/// Retry settings used when none are given on the command line
+pub fn retry_config() -> RetryConfig {
+    RetryConfig {
+        max_attempts: DEFAULT_RETRIES + 1,
+        initial_delay: Duration::from_millis(DEFAULT_RETRY_INITIAL_DELAY_MS),
+        max_delay: Duration::from_millis(DEFAULT_RETRY_MAX_DELAY_MS),
+        strategy: BackoffStrategy::default(),
+    }
+}
+
+/// Retry settings from `--retries`, `--retry-initial-delay` and `--retry-max-delay`
+fn retry_config_for(args: &StreamArgs) -> RetryConfig {
+    RetryConfig {
+        max_attempts: args.retries + 1,
+        initial_delay: Duration::from_millis(args.retry_initial_delay),
+        max_delay: Duration::from_millis(args.retry_max_delay),
+        ..retry_config()
+    }
+}
+
+/// Send one batch, retrying transient failures
+async fn send_batch(
+    client: &Client,
+    retry: &RetryConfig,
+    batch: &[Value],
+    pipeline: Option<&str>,
+    workspace: Option<&str>,
+) -> Result<()> {
+    retry_with_backoff(retry, || async {
+        client.stream_batch(batch, pipeline, workspace).await
+    })
+    .await?;
+    Ok(())
+}
+
+/// Tail a growing file, streaming new lines as they are appended until Ctrl+C
+async fn follow(
+    client: &Client,
+    args: &StreamArgs,
+    limits: &BatchLimits,
+    projection: &Projection,
+    sinks: (Option<&DeadLetter>, Option<&Journal>),
+    signing_key: Option<&SigningKey>,
+) -> Result<()> {
+    let (dead_letter, journal) = sinks;
+    let file = args.file.as_str();
+    let flush_interval = args.flush_interval.map(Duration::from_secs);
+    let batch_size = batch_size_for(limits);
+
+    println!("{} Following {} (Ctrl+C to stop)...", "ℹ".blue(), file);
 
-    // Process in batches
-    let batch_size = DEFAULT_BATCH_SIZE;
+    let mut tail = TailedFile::open(file).await?;
+    let mut line = String::new();
+    let mut batch = Vec::new();
     let mut successful = 0;
     let mut failed = 0;
+    let mut parse_errors = 0;
+    let mut filtered_out = 0;
+    let mut sampled_out = 0;
+    let mut aborted = false;
+    let mut pending_since = Instant::now();
 
-    for batch in events.chunks(batch_size) {
-        let batch_num = (batch.len() + batch_size - 1) / batch_size;
-        pb.set_message(&format!("Processing batch {}...", batch_num));
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
 
-        let retry_config = RetryConfig {
-            max_attempts: 3,
-            initial_delay: Duration::from_millis(100),
-            max_delay: Duration::from_secs(5),
-            backoff_multiplier: 2.0,
+    loop {
+        let read = tokio::select! {
+            _ = &mut ctrl_c => break,
+            read = tail.reader.read_line(&mut line) => read
+                .with_context(|| format!("Failed to read file: {}", file))?,
         };
 
-        match retry_with_backoff(&retry_config, || async {
-            client.stream_batch(batch, pipeline).await
-        })
-        .await
-        {
-            Ok(_) => {
-                successful += batch.len();
-                debug!("Batch {} processed successfully", batch_num);
+        if read > 0 {
+            // Hold on to partial lines until the writer finishes them
+            if !line.ends_with('\n') {
+                continue;
             }
-            Err(e) => {
-                failed += batch.len();
-                warn!("Batch {} failed: {}", batch_num, e);
-                // Continue with next batch instead of failing completely
+
+            // Like EventReader, skip a BOM (at the start of the file or left by
+            // concatenating files)
+            let text = line.trim().trim_start_matches('\u{feff}');
+            if !text.is_empty() {
+                match serde_json::from_str::<Value>(text) {
+                    Ok(event) if !in_time_window(&event, args) => filtered_out += 1,
+                    Ok(event) if args.sample.is_some_and(|rate| !sampled_in(&event, rate)) => {
+                        sampled_out += 1
+                    }
+                    Ok(mut event) => {
+                        projection.apply(&mut event);
+                        if !args.meta.is_empty() {
+                            inject_metadata(&mut event, &args.meta, args.meta_override);
+                        }
+                        if let Some(key) = signing_key {
+                            sign_value(&mut event, key).map_err(|e| {
+                                Error::Validation(format!("{:#}", e.context(text.to_string())))
+                            })?;
+                        }
+                        batch.push(event);
+                    }
+                    Err(e) => {
+                        warn!("Failed to parse line: {}", e);
+                        parse_errors += 1;
+                    }
+                }
+            }
+            line.clear();
+        }
+
+        // Without an interval, flush whenever we catch up with the writer
+        let due = match flush_interval {
+            Some(interval) => pending_since.elapsed() >= interval,
+            None => read == 0,
+        };
+
+        if !batch.is_empty() && (batch.len() >= batch_size || due) {
+            let (sent, lost) = flush_follow_batch(client, &batch, args, limits, sinks).await;
+            successful += sent;
+            failed += lost;
+            batch.clear();
+            if lost > 0 && args.on_error == OnError::Abort {
+                aborted = true;
+                break;
             }
         }
 
-        pb.inc(batch.len() as u64);
+        // Measure the interval from when the oldest pending event arrived
+        if batch.is_empty() {
+            pending_since = Instant::now();
+        }
+
+        if read == 0 {
+            tokio::select! {
+                _ = &mut ctrl_c => break,
+                _ = sleep(FOLLOW_POLL_INTERVAL) => {}
+            }
+
+            if tail.rotated(file).await {
+                info!("{} was rotated, reopening", file);
+                tail = TailedFile::open(file).await?;
+                line.clear();
+            }
+        }
     }
 
-    pb.finish_with_message("Complete");
+    if aborted {
+        println!("\n{} A batch failed (--on-error abort), stopping", "✗".red());
+    } else {
+        println!("\n{} Stopping, flushing pending events...", "ℹ".blue());
+    }
+    if !batch.is_empty() {
+        let (sent, lost) = flush_follow_batch(client, &batch, args, limits, sinks).await;
+        successful += sent;
+        failed += lost;
+    }
 
-    if successful > 0 {
+    println!(
+        "{} Streamed {} events successfully!",
+        "✓".green(),
+        successful
+    );
+    if parse_errors > 0 {
+        println!("{} {} lines failed to parse", "⚠".yellow(), parse_errors);
+    }
+    if filtered_out > 0 {
+        println!("{} {} events outside the time window", "ℹ".blue(), filtered_out);
+    }
+    if let Some(rate) = args.sample {
         println!(
-            "{} Streamed {} events successfully!",
-            "✓".green(),
-            successful
+            "{} Sampling {:.1}%: sampled out {} events",
+            "ℹ".blue(),
+            rate * 100.0,
+            sampled_out
         );
     }
-
     if failed > 0 {
         println!("{} {} events failed to stream", "✗".red(), failed);
     }
+    report_dead_letter(dead_letter);
+    report_journal(journal);
 
+    if aborted {
+        anyhow::bail!("stopped following after a batch failed; {} events failed", failed);
+    }
     Ok(())
 }
 
+/// Validate and send a batch in follow mode, returning (sent, failed) counts
+async fn flush_follow_batch(
+    client: &Client,
+    batch: &[Value],
+    args: &StreamArgs,
+    limits: &BatchLimits,
+    (dead_letter, journal): (Option<&DeadLetter>, Option<&Journal>),
+) -> (usize, usize) {
+    if !args.no_validate {
+        let validation = validate_batch(batch, limits);
+        if !validation.is_valid {
+            for error in &validation.errors {
+                warn!("Dropping invalid batch: {}", error);
+            }
+            write_dead_letter(dead_letter, batch, &validation.errors.join("; "));
+            return (0, batch.len());
+        }
+    }
+
+    let groups = match &args.pipeline_field {
+        Some(field) => group_by_pipeline(batch.to_vec(), field),
+        None => vec![(None, batch.to_vec())],
+    };
+
+    let (mut sent, mut failed) = (0, 0);
+    for (pipeline, events) in groups {
+        let pipeline = pipeline.as_deref().or(args.pipeline.as_deref());
+        let workspace = args.target_workspace.as_deref();
+        match send_batch(client, &retry_config_for(args), &events, pipeline, workspace).await {
+            Ok(_) => {
+                debug!("Streamed {} appended events", events.len());
+                if let Some(journal) = journal {
+                    if let Err(e) = journal.record(&events) {
+                        warn!("Failed to write to {}: {}", journal.path().display(), e);
+                    }
+                }
+                sent += events.len();
+            }
+            Err(e) => {
+                warn!("Batch failed: {}", e);
+                write_dead_letter(dead_letter, &events, &e.to_string());
+                failed += events.len();
+            }
+        }
+    }
+    (sent, failed)
+}
+
+fn write_dead_letter(dead_letter: Option<&DeadLetter>, events: &[Value], error: &str) {
+    if let Some(dead_letter) = dead_letter {
+        if let Err(e) = dead_letter.write(events, error) {
+            warn!("Failed to write to {}: {}", dead_letter.path().display(), e);
+        }
+    }
+}
+
+/// A file opened for tailing, remembering which inode it was opened on
+struct TailedFile {
+    reader: AsyncBufReader<tokio::fs::File>,
+    file_id: Option<u64>,
+}
+
+impl TailedFile {
+    async fn open(path: &str) -> Result<Self> {
+        let file = tokio::fs::File::open(path)
+            .await
+            .with_context(|| format!("Failed to open file: {}", path))?;
+        let file_id = file_id(&file.metadata().await?);
+
+        Ok(Self {
+            reader: AsyncBufReader::new(file),
+            file_id,
+        })
+    }
+
+    /// Whether the path now points at a different or truncated file
+    async fn rotated(&mut self, path: &str) -> bool {
+        let Ok(metadata) = tokio::fs::metadata(path).await else {
+            // Mid-rotation the path may briefly not exist; keep reading the old handle
+            return false;
+        };
+
+        if file_id(&metadata) != self.file_id {
+            return true;
+        }
+
+        match self.reader.stream_position().await {
+            Ok(position) => metadata.len() < position,
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(unix)]
+fn file_id(metadata: &std::fs::Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.ino())
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &std::fs::Metadata) -> Option<u64> {
+    None
+}
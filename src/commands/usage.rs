@@ -0,0 +1,99 @@
+//! Workspace quota and usage for the current period
+use anyhow::{Context, Result};
+use colored::*;
+use pynthora_terminal::core::config::Config;
+use pynthora_terminal::sdk::client::{Client, Usage};
+
+/// Width of the usage bar, in cells
+const BAR_WIDTH: usize = 30;
+
+/// Share of the quota from which the bar turns yellow, then red
+const WARN_FRACTION: f64 = 0.75;
+const CRITICAL_FRACTION: f64 = 0.9;
+
+pub async fn run() -> Result<()> {
+    let config = Config::load(None)?;
+    let client = Client::new(config)?;
+
+    let usage = client.get_usage().await.context("Failed to fetch usage")?;
+
+    println!("\n{} Usage for {}", "=".cyan().bold(), client.workspace());
+    match usage.fraction_used() {
+        Some(fraction) => {
+            println!("  {} {:.1}%", colored_bar(fraction), fraction * 100.0);
+            println!(
+                "  Events: {} of {} ({} remaining)",
+                usage.events_used,
+                usage.quota.unwrap_or_default(),
+                usage.remaining().unwrap_or_default()
+            );
+        }
+        None => println!("  Events: {} (no quota)", usage.events_used),
+    }
+    if let Some(resets_at) = &usage.resets_at {
+        println!("  Resets: {}", resets_at);
+    }
+
+    Ok(())
+}
+
+/// Why sending `events` would go past the remaining quota, for `stream --check-quota`
+pub fn quota_warning(usage: &Usage, events: usize) -> Option<String> {
+    let remaining = usage.remaining()?;
+    if events as u64 <= remaining {
+        return None;
+    }
+
+    let resets = usage
+        .resets_at
+        .as_deref()
+        .map(|at| format!("; the quota resets at {}", at))
+        .unwrap_or_default();
+    Some(format!(
+        "This file has {} events but only {} remain in the workspace quota{}",
+        events, remaining, resets
+    ))
+}
+
+fn colored_bar(fraction: f64) -> ColoredString {
+    let bar = bar(fraction, BAR_WIDTH);
+    if fraction >= CRITICAL_FRACTION {
+        bar.red()
+    } else if fraction >= WARN_FRACTION {
+        bar.yellow()
+    } else {
+        bar.green()
+    }
+}
+
+/// `[████░░░░]` with `fraction` of `width` cells filled, capped at full
+fn bar(fraction: f64, width: usize) -> String {
+    let filled = ((fraction.clamp(0.0, 1.0) * width as f64).round() as usize).min(width);
+    format!("[{}{}]", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bar_is_capped() {
+        assert_eq!(bar(0.0, 4), "[░░░░]");
+        assert_eq!(bar(0.5, 4), "[██░░]");
+        assert_eq!(bar(1.7, 4), "[████]");
+    }
+
+    #[test]
+    fn test_quota_warning() {
+        let usage = Usage {
+            events_used: 900,
+            quota: Some(1000),
+            resets_at: None,
+        };
+        assert!(quota_warning(&usage, 100).is_none());
+        assert!(quota_warning(&usage, 101).unwrap().contains("only 100 remain"));
+
+        let unlimited = Usage { quota: None, ..usage };
+        assert!(quota_warning(&unlimited, 1_000_000).is_none());
+    }
+}
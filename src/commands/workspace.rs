@@ -0,0 +1,88 @@
+use anyhow::Result;
+use colored::*;
+use futures_util::future;
+use pynthora_terminal::core::config::{Config, Overrides};
+use pynthora_terminal::core::error::Error;
+use pynthora_terminal::core::workspace::{Workspace, WorkspaceManager};
+use pynthora_terminal::sdk::client::Client;
+use std::path::Path;
+use std::time::Instant;
+
+/// Register an existing config file as a named workspace
+pub async fn import(config_path: &str, name: Option<&str>) -> Result<()> {
+    let (_, errors, _) = Config::check(Some(config_path), &Overrides::default())?;
+    if !errors.is_empty() {
+        return Err(Error::Config(format!(
+            "{} is invalid: {}",
+            config_path,
+            errors.join("; ")
+        ))
+        .into());
+    }
+    let config = Config::from_file(Path::new(config_path))?;
+
+    let name = name.unwrap_or(&config.workspace);
+    let mut manager = WorkspaceManager::load()?;
+    let replaced = manager.list().iter().any(|w| w.name == name);
+    manager.add_workspace(Workspace::from_config(&config, name))?;
+
+    if replaced {
+        println!("{} Replaced workspace '{}' with {}", "⚠".yellow(), name, config_path);
+    } else {
+        println!("{} Imported {} as workspace '{}'", "✓".green(), config_path, name);
+    }
+    Ok(())
+}
+
+/// Health-check every registered workspace concurrently and print a reachability table
+pub async fn check() -> Result<()> {
+    let manager = WorkspaceManager::load()?;
+    let mut workspaces = manager.list();
+    if workspaces.is_empty() {
+        println!("{} No workspaces registered", "ℹ".yellow());
+        return Ok(());
+    }
+    workspaces.sort_by(|a, b| a.name.cmp(&b.name));
+
+    println!("{} Checking {} workspaces...", "ℹ".blue(), workspaces.len());
+    let results = future::join_all(workspaces.iter().map(|workspace| async move {
+        let client = Client::new(workspace.to_config())?;
+        let started = Instant::now();
+        client.health_check(true).await?;
+        Ok::<_, Error>(started.elapsed().as_millis())
+    }))
+    .await;
+
+    println!("{:<24} {:<12} {:>10}  ENDPOINT", "WORKSPACE", "STATUS", "LATENCY");
+    let mut unreachable = 0;
+    for (workspace, result) in workspaces.iter().zip(&results) {
+        match result {
+            Ok(latency) => println!(
+                "{:<24} {:<12} {:>10}  {}",
+                workspace.name,
+                "reachable".green(),
+                format!("{}ms", latency),
+                workspace.ingest_url
+            ),
+            Err(e) => {
+                unreachable += 1;
+                // Gateways can answer with whole HTML pages; the first line is enough here
+                let message = e.to_string();
+                println!(
+                    "{:<24} {:<12} {:>10}  {} ({})",
+                    workspace.name,
+                    "unreachable".red(),
+                    "-",
+                    workspace.ingest_url,
+                    message.lines().next().unwrap_or_default()
+                );
+            }
+        }
+    }
+
+    if unreachable > 0 {
+        anyhow::bail!("{} of {} workspaces are unreachable", unreachable, workspaces.len());
+    }
+    println!("{} All workspaces reachable", "✓".green());
+    Ok(())
+}
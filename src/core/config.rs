@@ -1,34 +1,156 @@
 use anyhow::{Context, Result};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
+use tracing::{debug, warn};
 use url::Url;
-use validator::Validate;
+use validator::{Validate, ValidationError};
+
+use crate::core::validation::BatchLimits;
 
 static CACHED_CONFIG: OnceLock<Config> = OnceLock::new();
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+const DEFAULT_INGEST_URL: &str = "https://api.pynthora.network/ingest";
+
+const DEFAULT_API_PREFIX: &str = "/api/v1";
+
+/// Config fields settable from the environment, with their variables
+const ENV_VARS: &[(&str, &str)] = &[
+    ("api_key", "PYNTHORA_API_KEY"),
+    ("workspace", "PYNTHORA_WORKSPACE"),
+    ("ingest_url", "PYNTHORA_INGEST_URL"),
+    ("ingest_urls", "PYNTHORA_INGEST_URLS"),
+    ("api_prefix", "PYNTHORA_API_PREFIX"),
+    ("region", "PYNTHORA_REGION"),
+];
+
+/// Regions the multi-region gateway is known to route; others are sent with a warning
+pub const KNOWN_REGIONS: &[&str] = &["us-east", "us-west", "eu-west", "eu-central", "ap-southeast"];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate)]
 pub struct Config {
     #[validate(length(min = 16))]
     pub api_key: String,
 
+    /// Primary ingest endpoint (filled from `ingest_urls` when omitted)
     #[validate(url)]
+    #[serde(default)]
     pub ingest_url: String,
 
+    /// Additional ingest endpoints tried in order when the primary is unreachable
+    #[validate(custom(function = "validate_urls"))]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ingest_urls: Vec<String>,
+
     #[validate(length(min = 1))]
     pub workspace: String,
+
+    /// Batch size limits for self-hosted gateways (defaults apply when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch_limits: Option<BatchLimits>,
+
+    /// Timeout for health checks in seconds (default: 5)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health_timeout_secs: Option<u64>,
+
+    /// Idle connections kept open per host (default: 10)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool_max_idle_per_host: Option<usize>,
+
+    /// Seconds an idle pooled connection is kept alive (default: 90)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool_idle_timeout_secs: Option<u64>,
+
+    /// Pipeline used when a command doesn't specify one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_pipeline: Option<String>,
+
+    /// Headers sent with every request
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub extra_headers: HashMap<String, String>,
+
+    /// Headers whose values are read from environment variables on every request,
+    /// for short-lived tokens that rotate (header name -> variable name)
+    #[validate(custom(function = "validate_env_headers"))]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env_headers: HashMap<String, String>,
+
+    /// How the API key is sent: `bearer` (default), `x-api-key`, or a custom header name
+    #[validate(custom(function = "validate_auth_scheme"))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_scheme: Option<AuthScheme>,
+
+    /// User-Agent sent with every request (default: `pynthora-terminal/<version>`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+
+    /// Path prefix for API routes, for gateways mounted behind a reverse proxy
+    /// (default: `/api/v1`; use `""` for routes at the root)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_prefix: Option<String>,
+
+    /// Region sent as `X-Region` on every request, for gateways that route on it.
+    /// `PYNTHORA_REGION` (or `--region`) overrides the config file
+    #[validate(custom(function = "validate_region"))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+
+    /// Oldest gateway version (semver) this config is known to work with; commands
+    /// refuse to run against older servers
+    #[validate(custom(function = "validate_semver"))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_server_version: Option<String>,
+
+    /// Ed25519 key used by `stream --sign` (PKCS#8 PEM or a hex-encoded 32-byte seed)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_key_path: Option<PathBuf>,
+
+    /// PEM file with an extra root CA, for gateways behind a private CA
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_cert_path: Option<PathBuf>,
+
+    /// PEM client certificate for mutual TLS (requires `client_key_path`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_cert_path: Option<PathBuf>,
+
+    /// PEM PKCS#8 private key for `client_cert_path`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_key_path: Option<PathBuf>,
+
+    /// DANGER: skip TLS certificate verification. Only for local dev gateways with
+    /// self-signed certs; never enable this against a real endpoint.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub danger_accept_invalid_certs: bool,
+
+    /// Append a record of every command run to `~/.pynthora/history.ndjson`, as if
+    /// `--record-history` were always passed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub record_history: bool,
 }
 
 impl Config {
+    /// Start building a config programmatically
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::default()
+    }
+
     /// Load configuration from file or environment variables
     pub fn load(custom_path: Option<&str>) -> Result<&'static Config> {
+        Self::load_with(custom_path, &Overrides::from_env())
+    }
+
+    /// Like [`Config::load`], with `overrides` (e.g. from command-line flags) in place
+    /// of the `PYNTHORA_PROFILE` and `PYNTHORA_REGION` environment variables
+    pub fn load_with(custom_path: Option<&str>, overrides: &Overrides) -> Result<&'static Config> {
         if let Some(config) = CACHED_CONFIG.get() {
             return Ok(config);
         }
 
         // Try environment variables first
-        if let Some(config) = Self::from_env()? {
+        if let Some(config) = Self::from_env(overrides)? {
             CACHED_CONFIG.set(config).map_err(|_| {
                 anyhow::anyhow!("Failed to cache config")
             })?;
@@ -37,7 +159,9 @@ impl Config {
 
         // Try to load from file
         let config_path = Self::resolve_config_path(custom_path)?;
-        let config = Self::from_file(&config_path)?;
+        let mut config = Self::read_file(&config_path, overrides.profile.as_deref())?;
+        overrides.apply(&mut config);
+        config.validate()?;
         
         CACHED_CONFIG.set(config).map_err(|_| {
             anyhow::anyhow!("Failed to cache config")
@@ -47,33 +171,70 @@ impl Config {
     }
 
     /// Load config from environment variables
-    fn from_env() -> Result<Option<Config>> {
+    fn from_env(overrides: &Overrides) -> Result<Option<Config>> {
+        let Some(mut config) = Self::read_env() else {
+            return Ok(None);
+        };
+        overrides.apply(&mut config);
+
+        config.validate()?;
+        Ok(Some(config))
+    }
+
+    /// Build a normalized but unvalidated config from environment variables
+    fn read_env() -> Option<Config> {
         let api_key = std::env::var("PYNTHORA_API_KEY").ok();
         let workspace = std::env::var("PYNTHORA_WORKSPACE").ok();
 
         if api_key.is_none() || workspace.is_none() {
-            return Ok(None);
+            return None;
         }
 
-        let config = Config {
+        let mut config = Config {
             api_key: api_key.unwrap(),
-            ingest_url: std::env::var("PYNTHORA_INGEST_URL")
-                .unwrap_or_else(|_| "https://api.pynthora.network/ingest".to_string()),
+            ingest_url: std::env::var("PYNTHORA_INGEST_URL").unwrap_or_default(),
+            ingest_urls: std::env::var("PYNTHORA_INGEST_URLS")
+                .map(|urls| urls.split(',').map(|u| u.trim().to_string()).collect())
+                .unwrap_or_default(),
             workspace: workspace.unwrap(),
+            api_prefix: std::env::var("PYNTHORA_API_PREFIX").ok(),
+            region: std::env::var("PYNTHORA_REGION").ok(),
+            ..Default::default()
         };
 
+        if config.ingest_url.is_empty() && config.ingest_urls.is_empty() {
+            config.ingest_url = DEFAULT_INGEST_URL.to_string();
+        }
+
+        config.normalize_endpoints();
+        Some(config)
+    }
+
+    /// Load and validate a specific config file, bypassing env vars and the cache; a
+    /// profiles-style file resolves to its `default_profile`
+    pub fn from_file(path: &Path) -> Result<Config> {
+        let config = Self::read_file(path, None)?;
         config.validate()?;
-        Ok(Some(config))
+        Ok(config)
+    }
+
+    /// Parse and normalize a config file without validating it, using `profile` (or
+    /// the file's `default_profile`) when it has profiles
+    fn read_file(path: &Path, profile: Option<&str>) -> Result<Config> {
+        let raw = Self::read_raw(path, profile)?;
+        let mut config: Config =
+            serde_json::from_value(raw).with_context(|| "Invalid config file")?;
+
+        config.normalize_endpoints();
+        Ok(config)
     }
 
-    /// Load config from file
-    fn from_file(path: &Path) -> Result<Config> {
+    /// Parse a config file into its settings, resolving the selected profile if it has any
+    fn read_raw(path: &Path, profile: Option<&str>) -> Result<serde_json::Value> {
         let content = fs::read_to_string(path)
             .with_context(|| format!("Failed to read config file: {}", path.display()))?;
 
-        let config: Config = if path.extension().and_then(|s| s.to_str()) == Some("yaml")
-            || path.extension().and_then(|s| s.to_str()) == Some("yml")
-        {
+        let raw = if is_yaml(path) {
             serde_yaml::from_str(&content)
                 .with_context(|| "Failed to parse YAML config")?
         } else {
@@ -81,8 +242,139 @@ impl Config {
                 .with_context(|| "Failed to parse JSON config")?
         };
 
-        config.validate()?;
-        Ok(config)
+        select_profile(raw, profile)
+    }
+
+    /// Load a config the way `load` would, without caching it, and return every
+    /// validation error rather than stopping at the first, plus warnings about settings
+    /// that are valid but suspicious. An explicit path wins over environment variables.
+    pub fn check(
+        custom_path: Option<&str>,
+        overrides: &Overrides,
+    ) -> Result<(ConfigSource, Vec<String>, Vec<String>)> {
+        let (source, config) = Self::resolve(custom_path, overrides)?;
+
+        let mut errors: Vec<String> = match config.validate() {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors
+                .field_errors()
+                .into_iter()
+                .flat_map(|(field, errors)| {
+                    errors.iter().map(move |error| match &error.message {
+                        Some(message) => format!("{}: {}", field, message),
+                        None => format!("{}: failed '{}' check", field, error.code),
+                    })
+                })
+                .collect(),
+        };
+        errors.sort();
+
+        let warnings = config.region_warning().into_iter().collect();
+        Ok((source, errors, warnings))
+    }
+
+    /// The effective config, unvalidated and uncached, plus where each of its settings
+    /// came from (an env var, the config file, or a built-in default)
+    pub fn explain(
+        custom_path: Option<&str>,
+        overrides: &Overrides,
+    ) -> Result<(ConfigSource, Config, BTreeMap<String, SettingOrigin>)> {
+        let (source, config) = Self::resolve(custom_path, overrides)?;
+
+        let file_keys: Vec<String> = match &source {
+            ConfigSource::File(path) => Self::read_raw(path, overrides.profile.as_deref())?
+                .as_object()
+                .map(|obj| obj.keys().cloned().collect())
+                .unwrap_or_default(),
+            ConfigSource::Env => Vec::new(),
+        };
+
+        let effective = serde_json::to_value(&config)?;
+        let origins = effective
+            .as_object()
+            .into_iter()
+            .flat_map(|obj| obj.keys())
+            .map(|key| {
+                let origin = match &source {
+                    _ if key == "region" && overrides.region.is_some() => SettingOrigin::Override,
+                    ConfigSource::Env => ENV_VARS
+                        .iter()
+                        .find(|(field, var)| field == key && std::env::var(var).is_ok())
+                        .map_or(SettingOrigin::Default, |(_, var)| SettingOrigin::Env(var)),
+                    // A primary promoted from `ingest_urls` still came from the file
+                    ConfigSource::File(_) if key == "ingest_url" && file_keys.iter().any(|k| k.starts_with("ingest_url")) => SettingOrigin::File,
+                    ConfigSource::File(_) if file_keys.contains(key) => SettingOrigin::File,
+                    ConfigSource::File(_) => SettingOrigin::Default,
+                };
+                (key.clone(), origin)
+            })
+            .collect();
+
+        Ok((source, config, origins))
+    }
+
+    /// Pick env vars or a config file the way `load` does, except an explicit path wins
+    fn resolve(
+        custom_path: Option<&str>,
+        overrides: &Overrides,
+    ) -> Result<(ConfigSource, Config)> {
+        let (source, mut config) = match (custom_path, Self::read_env()) {
+            (None, Some(config)) => (ConfigSource::Env, config),
+            _ => {
+                let path = Self::resolve_config_path(custom_path)?;
+                let config = Self::read_file(&path, overrides.profile.as_deref())?;
+                (ConfigSource::File(path), config)
+            }
+        };
+        overrides.apply(&mut config);
+        Ok((source, config))
+    }
+
+    /// Ingest endpoints in failover order, primary first
+    pub fn endpoints(&self) -> Vec<&str> {
+        let mut endpoints = vec![self.ingest_url.as_str()];
+        for url in &self.ingest_urls {
+            if !endpoints.contains(&url.as_str()) {
+                endpoints.push(url);
+            }
+        }
+        endpoints
+    }
+
+    /// Prefix joined between an endpoint and a route, with a leading slash and no
+    /// trailing one (empty when routes are served at the root)
+    pub fn api_prefix(&self) -> String {
+        let prefix = self.api_prefix.as_deref().unwrap_or(DEFAULT_API_PREFIX);
+        match prefix.trim_matches('/') {
+            "" => String::new(),
+            trimmed => format!("/{}", trimmed),
+        }
+    }
+
+    /// Why the configured region looks wrong, if it isn't one of `KNOWN_REGIONS`
+    pub fn region_warning(&self) -> Option<String> {
+        let region = self.region.as_deref()?;
+        (!KNOWN_REGIONS.contains(&region)).then(|| {
+            format!(
+                "region '{}' is not a known region ({}); sending it anyway",
+                region,
+                KNOWN_REGIONS.join(", ")
+            )
+        })
+    }
+
+    /// Promote the first of `ingest_urls` to primary when `ingest_url` is omitted, and
+    /// strip a trailing slash so joined paths don't produce `//api/v1/...`
+    pub(crate) fn normalize_endpoints(&mut self) {
+        if self.ingest_url.is_empty() && !self.ingest_urls.is_empty() {
+            self.ingest_url = self.ingest_urls.remove(0);
+        }
+
+        for url in std::iter::once(&mut self.ingest_url).chain(self.ingest_urls.iter_mut()) {
+            if let Some(stripped) = url.strip_suffix('/') {
+                *url = stripped.to_string();
+            }
+        }
     }
 
     /// Resolve config file path
@@ -128,6 +420,406 @@ impl Config {
             .unwrap_or_else(|_| PathBuf::from("."))
             .join(".pynthorarc.json")
     }
+
+    /// Watch a config file and call `on_change` with the freshly loaded, validated
+    /// config each time it changes; watching stops when the returned handle is dropped.
+    ///
+    /// The parent directory is watched so editors that save by renaming a temp file
+    /// are picked up too. Writes that leave the file unparseable or invalid (e.g. a
+    /// half-written save) are logged and skipped, as are changes that don't alter
+    /// any setting.
+    pub fn watch<F>(path: impl AsRef<Path>, mut on_change: F) -> Result<ConfigWatcher>
+    where
+        F: FnMut(Config) + Send + 'static,
+    {
+        let path = path.as_ref().to_path_buf();
+        let dir = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_os_string())
+            .with_context(|| format!("Not a config file path: {}", path.display()))?;
+
+        let mut last = Self::from_file(&path)
+            .ok()
+            .and_then(|config| serde_json::to_value(config).ok());
+
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            let event = match event {
+                Ok(event) => event,
+                Err(e) => {
+                    warn!("Config watch error: {}", e);
+                    return;
+                }
+            };
+            if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                || !event.paths.iter().any(|p| p.file_name() == Some(file_name.as_os_str()))
+            {
+                return;
+            }
+
+            let config = match Self::from_file(&path) {
+                Ok(config) => config,
+                Err(e) => {
+                    debug!("Ignoring config change to {}: {:#}", path.display(), e);
+                    return;
+                }
+            };
+
+            // A single save often fires several events
+            let snapshot = serde_json::to_value(&config).ok();
+            if snapshot.is_some() && snapshot == last {
+                return;
+            }
+            last = snapshot;
+            on_change(config);
+        })
+        .context("Failed to start config watcher")?;
+
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .with_context(|| format!("Failed to watch {}", dir.display()))?;
+
+        Ok(ConfigWatcher { _watcher: watcher })
+    }
+}
+
+/// Handle returned by `Config::watch`; dropping it stops watching
+pub struct ConfigWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+/// Header carrying the API key, written in config files as `bearer`, `x-api-key`
+/// or any other header name
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum AuthScheme {
+    /// `Authorization: Bearer <key>`
+    #[default]
+    Bearer,
+    /// `X-Api-Key: <key>`
+    XApiKey,
+    /// The bare key in the named header
+    Header(String),
+}
+
+impl AuthScheme {
+    pub fn header_name(&self) -> &str {
+        match self {
+            AuthScheme::Bearer => "Authorization",
+            AuthScheme::XApiKey => "X-Api-Key",
+            AuthScheme::Header(name) => name,
+        }
+    }
+
+    pub fn header_value(&self, api_key: &str) -> String {
+        match self {
+            AuthScheme::Bearer => format!("Bearer {}", api_key),
+            AuthScheme::XApiKey | AuthScheme::Header(_) => api_key.to_string(),
+        }
+    }
+}
+
+impl From<String> for AuthScheme {
+    fn from(value: String) -> Self {
+        match value.to_ascii_lowercase().as_str() {
+            "bearer" => AuthScheme::Bearer,
+            "x-api-key" => AuthScheme::XApiKey,
+            _ => AuthScheme::Header(value),
+        }
+    }
+}
+
+impl From<AuthScheme> for String {
+    fn from(scheme: AuthScheme) -> Self {
+        match scheme {
+            AuthScheme::Bearer => "bearer".to_string(),
+            AuthScheme::XApiKey => "x-api-key".to_string(),
+            AuthScheme::Header(name) => name,
+        }
+    }
+}
+
+/// Settings that select or override what a config file says, usually taken from
+/// command-line flags
+#[derive(Debug, Clone, Default)]
+pub struct Overrides {
+    /// Profile to use from a profiles-style config file
+    pub profile: Option<String>,
+    /// Gateway region, replacing the configured `region`
+    pub region: Option<String>,
+}
+
+impl Overrides {
+    /// Overrides from `PYNTHORA_PROFILE` and `PYNTHORA_REGION`
+    pub fn from_env() -> Self {
+        Self {
+            profile: std::env::var("PYNTHORA_PROFILE").ok(),
+            region: std::env::var("PYNTHORA_REGION").ok(),
+        }
+    }
+
+    fn apply(&self, config: &mut Config) {
+        if let Some(region) = &self.region {
+            config.region = Some(region.clone());
+        }
+    }
+}
+
+/// Where a config was loaded from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// `PYNTHORA_*` environment variables
+    Env,
+    File(PathBuf),
+}
+
+impl std::fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigSource::Env => f.write_str("environment variables"),
+            ConfigSource::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// Where an individual setting's value came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingOrigin {
+    Env(&'static str),
+    File,
+    /// A command-line flag or its environment variable, see [`Overrides`]
+    Override,
+    Default,
+}
+
+impl std::fmt::Display for SettingOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SettingOrigin::Env(var) => write!(f, "env {}", var),
+            SettingOrigin::File => f.write_str("file"),
+            SettingOrigin::Override => f.write_str("override"),
+            SettingOrigin::Default => f.write_str("default"),
+        }
+    }
+}
+
+/// Chainable constructor for `Config`, validated on `build()`
+#[derive(Debug, Clone, Default)]
+pub struct ConfigBuilder {
+    config: Config,
+}
+
+impl ConfigBuilder {
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.config.api_key = api_key.into();
+        self
+    }
+
+    pub fn ingest_url(mut self, ingest_url: impl Into<String>) -> Self {
+        self.config.ingest_url = ingest_url.into();
+        self
+    }
+
+    /// Add a fallback ingest endpoint
+    pub fn fallback_url(mut self, url: impl Into<String>) -> Self {
+        self.config.ingest_urls.push(url.into());
+        self
+    }
+
+    pub fn workspace(mut self, workspace: impl Into<String>) -> Self {
+        self.config.workspace = workspace.into();
+        self
+    }
+
+    pub fn batch_limits(mut self, limits: BatchLimits) -> Self {
+        self.config.batch_limits = Some(limits);
+        self
+    }
+
+    pub fn health_timeout_secs(mut self, secs: u64) -> Self {
+        self.config.health_timeout_secs = Some(secs);
+        self
+    }
+
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.config.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    pub fn pool_idle_timeout_secs(mut self, secs: u64) -> Self {
+        self.config.pool_idle_timeout_secs = Some(secs);
+        self
+    }
+
+    pub fn default_pipeline(mut self, pipeline: impl Into<String>) -> Self {
+        self.config.default_pipeline = Some(pipeline.into());
+        self
+    }
+
+    /// Add a header sent with every request
+    pub fn extra_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.config.extra_headers.insert(name.into(), value.into());
+        self
+    }
+
+    /// Send header `name` with the value of environment variable `var`, read per request
+    pub fn env_header(mut self, name: impl Into<String>, var: impl Into<String>) -> Self {
+        self.config.env_headers.insert(name.into(), var.into());
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.config.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Path prefix for API routes (default: `/api/v1`)
+    pub fn api_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.config.api_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Route requests to `region` with `X-Region`
+    pub fn region(mut self, region: impl Into<String>) -> Self {
+        self.config.region = Some(region.into());
+        self
+    }
+
+    /// Refuse to run against gateways older than `version` (semver)
+    pub fn min_server_version(mut self, version: impl Into<String>) -> Self {
+        self.config.min_server_version = Some(version.into());
+        self
+    }
+
+    /// Send the API key with `scheme` instead of `Authorization: Bearer`
+    pub fn auth_scheme(mut self, scheme: AuthScheme) -> Self {
+        self.config.auth_scheme = Some(scheme);
+        self
+    }
+
+    /// Ed25519 key used to sign events when streaming with `--sign`
+    pub fn signing_key_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.signing_key_path = Some(path.into());
+        self
+    }
+
+    pub fn ca_cert_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.ca_cert_path = Some(path.into());
+        self
+    }
+
+    /// Client certificate and PKCS#8 key for mutual TLS
+    pub fn client_cert(mut self, cert: impl Into<PathBuf>, key: impl Into<PathBuf>) -> Self {
+        self.config.client_cert_path = Some(cert.into());
+        self.config.client_key_path = Some(key.into());
+        self
+    }
+
+    /// Normalize endpoints and validate, defaulting the ingest URL when none was set
+    pub fn build(self) -> Result<Config> {
+        let mut config = self.config;
+        if config.ingest_url.is_empty() && config.ingest_urls.is_empty() {
+            config.ingest_url = DEFAULT_INGEST_URL.to_string();
+        }
+
+        config.normalize_endpoints();
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+/// Flatten a profiles-style config (`profiles: {name: {...}}` plus optional
+/// `default_profile`) into the settings of one profile.
+///
+/// Top-level settings outside `profiles` are shared by every profile, which can
+/// override them. Flat configs are returned unchanged.
+fn select_profile(raw: serde_json::Value, requested: Option<&str>) -> Result<serde_json::Value> {
+    let serde_json::Value::Object(mut settings) = raw else {
+        anyhow::bail!("Config file must contain a mapping of settings");
+    };
+
+    let Some(profiles) = settings.remove("profiles") else {
+        if let Some(name) = requested {
+            anyhow::bail!("Profile '{}' requested but the config file has no profiles", name);
+        }
+        return Ok(serde_json::Value::Object(settings));
+    };
+    let default_profile = settings.remove("default_profile");
+
+    let name = match (requested, default_profile.as_ref().and_then(|v| v.as_str())) {
+        (Some(name), _) | (None, Some(name)) => name.to_string(),
+        (None, None) => anyhow::bail!(
+            "Config file has profiles but no default_profile; pass --profile or set PYNTHORA_PROFILE"
+        ),
+    };
+
+    let profile = profiles.get(&name).with_context(|| {
+        let available: Vec<&String> = profiles
+            .as_object()
+            .map(|p| p.keys().collect())
+            .unwrap_or_default();
+        format!("Unknown profile '{}' (available: {:?})", name, available)
+    })?;
+    let Some(overrides) = profile.as_object() else {
+        anyhow::bail!("Profile '{}' must be a mapping of settings", name);
+    };
+
+    settings.extend(overrides.clone());
+    Ok(serde_json::Value::Object(settings))
+}
+
+fn is_yaml(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|s| s.to_str()),
+        Some("yaml") | Some("yml")
+    )
+}
+
+fn validate_urls(urls: &[String]) -> Result<(), ValidationError> {
+    if urls.iter().all(|url| Url::parse(url).is_ok()) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("url"))
+    }
+}
+
+fn validate_auth_scheme(scheme: &AuthScheme) -> Result<(), ValidationError> {
+    reqwest::header::HeaderName::from_bytes(scheme.header_name().as_bytes())
+        .map(|_| ())
+        .map_err(|_| ValidationError::new("header_name"))
+}
+
+fn validate_env_headers(headers: &HashMap<String, String>) -> Result<(), ValidationError> {
+    let valid = headers.iter().all(|(name, var)| {
+        reqwest::header::HeaderName::from_bytes(name.as_bytes()).is_ok() && !var.is_empty()
+    });
+    if valid {
+        Ok(())
+    } else {
+        Err(ValidationError::new("env_header"))
+    }
+}
+
+/// Region names are sent as a header, so keep them to letters, digits, `-` and `_`
+fn validate_region(region: &str) -> Result<(), ValidationError> {
+    let valid = !region.is_empty()
+        && region
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'));
+    if valid {
+        Ok(())
+    } else {
+        Err(ValidationError::new("region"))
+    }
+}
+
+fn validate_semver(version: &str) -> Result<(), ValidationError> {
+    semver::Version::parse(version)
+        .map(|_| ())
+        .map_err(|_| ValidationError::new("semver"))
 }
 
 #[cfg(test)]
@@ -141,18 +833,37 @@ mod tests {
             api_key: "test_key_12345678".to_string(),
             ingest_url: "https://api.pynthora.network/ingest".to_string(),
             workspace: "test-workspace".to_string(),
+            ..Default::default()
         };
 
         assert!(config.validate().is_ok());
     }
 
+    #[test]
+    fn test_config_builder() {
+        let config = Config::builder()
+            .api_key("test_key_12345678")
+            .ingest_url("https://eu.pynthora.network/ingest/")
+            .fallback_url("https://us.pynthora.network/ingest")
+            .workspace("test-workspace")
+            .default_pipeline("pipe-1")
+            .build()
+            .unwrap();
+
+        assert_eq!(config.ingest_url, "https://eu.pynthora.network/ingest");
+        assert_eq!(config.endpoints().len(), 2);
+        assert_eq!(config.default_pipeline.as_deref(), Some("pipe-1"));
+
+        assert!(Config::builder().api_key("short").workspace("ws").build().is_err());
+    }
+
     #[test]
     fn test_config_save_load() {
-        let config = Config {
-            api_key: "test_key_12345678".to_string(),
-            ingest_url: "https://api.pynthora.network/ingest".to_string(),
-            workspace: "test-workspace".to_string(),
-        };
+        let config = Config::builder()
+            .api_key("test_key_12345678")
+            .workspace("test-workspace")
+            .build()
+            .unwrap();
 
         let file = NamedTempFile::new().unwrap();
         config.save(file.path()).unwrap();
@@ -161,5 +872,172 @@ mod tests {
         assert_eq!(config.api_key, loaded.api_key);
         assert_eq!(config.workspace, loaded.workspace);
     }
-}
 
+    #[test]
+    fn test_config_ingest_urls_failover_order() {
+        let yaml = r#"
+api_key: test_key_12345678
+workspace: test-workspace
+ingest_urls:
+  - https://eu.pynthora.network/ingest
+  - https://us.pynthora.network/ingest
+"#;
+        let file = tempfile::Builder::new().suffix(".yaml").tempfile().unwrap();
+        fs::write(file.path(), yaml).unwrap();
+
+        let config = Config::from_file(file.path()).unwrap();
+        assert_eq!(config.ingest_url, "https://eu.pynthora.network/ingest");
+        assert_eq!(
+            config.endpoints(),
+            vec![
+                "https://eu.pynthora.network/ingest",
+                "https://us.pynthora.network/ingest"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_config_strips_trailing_slash() {
+        for ingest_url in [
+            "https://api.pynthora.network/ingest/",
+            "https://api.pynthora.network/ingest",
+        ] {
+            let mut config = Config {
+                api_key: "test_key_12345678".to_string(),
+                ingest_url: ingest_url.to_string(),
+                workspace: "test-workspace".to_string(),
+                ..Default::default()
+            };
+            config.normalize_endpoints();
+
+            assert_eq!(config.ingest_url, "https://api.pynthora.network/ingest");
+        }
+    }
+
+    #[test]
+    fn test_config_load_normalizes_trailing_slash() {
+        let file = NamedTempFile::new().unwrap();
+        fs::write(
+            file.path(),
+            r#"{"api_key": "test_key_12345678", "ingest_url": "https://api.pynthora.network/ingest/", "workspace": "test-workspace"}"#,
+        )
+        .unwrap();
+
+        let config = Config::from_file(file.path()).unwrap();
+        assert_eq!(config.ingest_url, "https://api.pynthora.network/ingest");
+    }
+
+    #[test]
+    fn test_select_profile() {
+        let raw = serde_json::json!({
+            "workspace": "shared",
+            "default_profile": "dev",
+            "profiles": {
+                "dev": {"api_key": "dev_key_123456789"},
+                "prod": {"api_key": "prod_key_12345678", "workspace": "prod-ws"}
+            }
+        });
+
+        let dev = select_profile(raw.clone(), None).unwrap();
+        assert_eq!(dev["api_key"], "dev_key_123456789");
+        assert_eq!(dev["workspace"], "shared");
+        assert!(dev.get("profiles").is_none());
+
+        let prod = select_profile(raw.clone(), Some("prod")).unwrap();
+        assert_eq!(prod["workspace"], "prod-ws");
+
+        assert!(select_profile(raw, Some("staging")).is_err());
+
+        let flat = serde_json::json!({"api_key": "flat_key_12345678"});
+        assert_eq!(select_profile(flat.clone(), None).unwrap(), flat);
+    }
+
+    #[test]
+    fn test_explain_uses_the_requested_profile() {
+        let file = NamedTempFile::new().unwrap();
+        fs::write(
+            file.path(),
+            r#"{"workspace": "shared", "default_profile": "dev", "profiles": {"dev": {"api_key": "dev_key_123456789"}, "prod": {"api_key": "prod_key_12345678"}}}"#,
+        )
+        .unwrap();
+        let path = file.path().to_str();
+
+        let (_, config, _) = Config::explain(path, &Overrides::default()).unwrap();
+        assert_eq!(config.api_key, "dev_key_123456789");
+
+        let overrides = Overrides {
+            profile: Some("prod".to_string()),
+            ..Default::default()
+        };
+        let (_, config, _) = Config::explain(path, &overrides).unwrap();
+        assert_eq!(config.api_key, "prod_key_12345678");
+    }
+
+    #[test]
+    fn test_region_override_replaces_the_file_region() {
+        let file = NamedTempFile::new().unwrap();
+        fs::write(
+            file.path(),
+            r#"{"api_key": "test_key_12345678", "ingest_url": "https://api.pynthora.network/ingest", "workspace": "test-workspace", "region": "eu-west"}"#,
+        )
+        .unwrap();
+        let overrides = Overrides {
+            region: Some("us-east".to_string()),
+            ..Default::default()
+        };
+
+        let (_, config, origins) = Config::explain(file.path().to_str(), &overrides).unwrap();
+        assert_eq!(config.region.as_deref(), Some("us-east"));
+        assert_eq!(origins["region"], SettingOrigin::Override);
+        assert_eq!(Config::from_file(file.path()).unwrap().region.as_deref(), Some("eu-west"));
+    }
+
+    #[test]
+    fn test_auth_scheme_from_config() {
+        for (raw, expected) in [
+            ("bearer", AuthScheme::Bearer),
+            ("X-API-KEY", AuthScheme::XApiKey),
+            ("X-Gateway-Token", AuthScheme::Header("X-Gateway-Token".to_string())),
+        ] {
+            let config: Config = serde_json::from_value(serde_json::json!({
+                "api_key": "test_key_12345678",
+                "workspace": "test-workspace",
+                "auth_scheme": raw,
+            }))
+            .unwrap();
+            assert_eq!(config.auth_scheme, Some(expected));
+        }
+
+        let result = Config::builder()
+            .api_key("test_key_12345678")
+            .workspace("test-workspace")
+            .auth_scheme(AuthScheme::Header("not a header".to_string()))
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_api_prefix_normalized() {
+        let mut config = Config::default();
+        assert_eq!(config.api_prefix(), "/api/v1");
+
+        config.api_prefix = Some("telemetry/api/v1/".to_string());
+        assert_eq!(config.api_prefix(), "/telemetry/api/v1");
+
+        config.api_prefix = Some(String::new());
+        assert_eq!(config.api_prefix(), "");
+    }
+
+    #[test]
+    fn test_region_is_validated() {
+        let builder = Config::builder().api_key("test_key_12345678").workspace("ws");
+
+        let config = builder.clone().region("eu-west").build().unwrap();
+        assert!(config.region_warning().is_none());
+
+        let config = builder.clone().region("mars-north").build().unwrap();
+        assert!(config.region_warning().unwrap().contains("mars-north"));
+
+        assert!(builder.region("eu west\n").build().is_err());
+    }
+}
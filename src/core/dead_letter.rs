@@ -0,0 +1,117 @@
+//! Dead-letter file for events that could not be delivered
+use serde_json::{Map, Value};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::core::error::Result;
+
+/// Field added to each dead-lettered event describing why it wasn't delivered
+pub const ERROR_FIELD: &str = "_error";
+
+/// Appends undelivered events as NDJSON so they can be inspected and replayed.
+///
+/// Each line is the original event with an `_error` field added; non-object events
+/// are wrapped as `{"event": ..., "_error": ...}`.
+pub struct DeadLetter {
+    path: PathBuf,
+    writer: Mutex<BufWriter<File>>,
+    written: AtomicUsize,
+}
+
+impl DeadLetter {
+    /// Open `path` for appending, creating it if needed
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Self {
+            path,
+            writer: Mutex::new(BufWriter::new(file)),
+            written: AtomicUsize::new(0),
+        })
+    }
+
+    /// Append `events`, tagging each with `error`, and flush so nothing is lost on exit
+    pub fn write(&self, events: &[Value], error: &str) -> Result<()> {
+        let mut writer = self.writer.lock().unwrap();
+        for event in events {
+            let line = match event {
+                Value::Object(obj) => {
+                    let mut obj = obj.clone();
+                    obj.insert(ERROR_FIELD.to_string(), Value::String(error.to_string()));
+                    Value::Object(obj)
+                }
+                other => {
+                    let mut obj = Map::new();
+                    obj.insert("event".to_string(), other.clone());
+                    obj.insert(ERROR_FIELD.to_string(), Value::String(error.to_string()));
+                    Value::Object(obj)
+                }
+            };
+            serde_json::to_writer(&mut *writer, &line).map_err(std::io::Error::from)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+
+        self.written.fetch_add(events.len(), Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Events written by this run
+    pub fn written(&self) -> usize {
+        self.written.load(Ordering::Relaxed)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Undo what `DeadLetter::write` added, recovering the original event
+pub fn strip_error(event: Value) -> Value {
+    let Value::Object(mut obj) = event else {
+        return event;
+    };
+    if obj.remove(ERROR_FIELD).is_none() {
+        return Value::Object(obj);
+    }
+
+    // Non-object events were wrapped as {"event": ..., "_error": ...}
+    match obj.remove("event") {
+        Some(inner) if obj.is_empty() && !inner.is_object() => inner,
+        Some(inner) => {
+            obj.insert("event".to_string(), inner);
+            Value::Object(obj)
+        }
+        None => Value::Object(obj),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_tags_events_with_error() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let dead_letter = DeadLetter::open(file.path()).unwrap();
+
+        let events = vec![serde_json::json!({"event_type": "a"}), serde_json::json!(42)];
+        dead_letter.write(&events, "503 Service Unavailable").unwrap();
+        assert_eq!(dead_letter.written(), 2);
+
+        let content = std::fs::read_to_string(file.path()).unwrap();
+        let lines: Vec<Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines[0]["event_type"], "a");
+        assert_eq!(lines[0][ERROR_FIELD], "503 Service Unavailable");
+        assert_eq!(lines[1]["event"], 42);
+
+        let restored: Vec<Value> = lines.into_iter().map(strip_error).collect();
+        assert_eq!(restored, events);
+    }
+}
@@ -0,0 +1,124 @@
+//! Structured error types for library consumers
+use thiserror::Error;
+
+/// Errors returned by the SDK, matchable by kind
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Configuration is missing or invalid
+    #[error("Configuration error: {0}")]
+    Config(String),
+
+    /// The gateway rejected our credentials (401/403)
+    #[error("Authentication failed ({status}): {body}{}", request_id_suffix(.request_id))]
+    Auth {
+        status: u16,
+        body: String,
+        request_id: Option<String>,
+    },
+
+    /// The request never produced a response (DNS, connect, timeout, ...)
+    #[error("Network error: {0}")]
+    Network(#[source] reqwest::Error),
+
+    /// The gateway's host name doesn't exist, most likely a typo in the config
+    #[error("could not resolve host {host} — check ingest_url")]
+    UnresolvedHost { host: String },
+
+    /// Client-side validation rejected the input
+    #[error("Validation failed: {0}")]
+    Validation(String),
+
+    /// The gateway answered with a non-success status
+    #[error("Server error {status}: {body}{}", request_id_suffix(.request_id))]
+    Server {
+        status: u16,
+        body: String,
+        request_id: Option<String>,
+    },
+
+    /// The gateway is older than the configured `min_server_version`
+    #[error(
+        "Gateway version {version} is older than {required}, required for {feature} \
+         (pass --skip-version-check to bypass)"
+    )]
+    UnsupportedServer {
+        version: String,
+        required: String,
+        feature: String,
+    },
+
+    /// The input file holds no parseable events (empty, blank lines only, or all malformed)
+    #[error("No events found in {0}")]
+    NoEvents(String),
+
+    /// An input event or gateway response could not be decoded
+    #[error("Failed to parse {0}")]
+    Decode(String),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Process exit codes, stable across releases so scripts can branch on them
+pub const EXIT_FAILURE: i32 = 1;
+pub const EXIT_CONFIG: i32 = 2;
+pub const EXIT_VALIDATION: i32 = 3;
+pub const EXIT_NETWORK: i32 = 4;
+pub const EXIT_AUTH: i32 = 5;
+pub const EXIT_NO_EVENTS: i32 = 6;
+
+impl Error {
+    /// Build the error matching an unsuccessful HTTP status
+    pub fn from_status(
+        status: reqwest::StatusCode,
+        body: String,
+        request_id: Option<String>,
+    ) -> Self {
+        match status.as_u16() {
+            401 | 403 => Error::Auth {
+                status: status.as_u16(),
+                body,
+                request_id,
+            },
+            code => Error::Server {
+                status: code,
+                body,
+                request_id,
+            },
+        }
+    }
+
+    /// Server-provided request id, to quote when contacting support
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            Error::Auth { request_id, .. } | Error::Server { request_id, .. } => {
+                request_id.as_deref()
+            }
+            _ => None,
+        }
+    }
+
+    /// Exit code for this error's category
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Config(_) => EXIT_CONFIG,
+            Error::Validation(_) => EXIT_VALIDATION,
+            Error::Network(_) | Error::UnresolvedHost { .. } => EXIT_NETWORK,
+            Error::Auth { .. } => EXIT_AUTH,
+            Error::NoEvents(_) => EXIT_NO_EVENTS,
+            Error::Server { .. }
+            | Error::UnsupportedServer { .. }
+            | Error::Decode(_)
+            | Error::Io(_) => EXIT_FAILURE,
+        }
+    }
+}
+
+fn request_id_suffix(request_id: &Option<String>) -> String {
+    request_id
+        .as_ref()
+        .map(|id| format!(" (request id: {})", id))
+        .unwrap_or_default()
+}
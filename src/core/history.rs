@@ -0,0 +1,73 @@
+//! Local history of the CLI's own runs (`--record-history`), one `TelemetryEvent`
+//! per invocation in `~/.pynthora/history.ndjson`
+use serde_json::Value;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::core::error::{Error, Result};
+use crate::core::telemetry::TelemetryEvent;
+
+/// `event_type` of history records
+pub const RUN_EVENT_TYPE: &str = "cli_run";
+
+/// Counts reported by the running command, picked up when its record is written
+static RUN_COUNTS: Mutex<Option<Value>> = Mutex::new(None);
+
+/// Attach `counts` (events sent, failed, ...) to the history record of this run
+pub fn record_counts(counts: Value) {
+    *RUN_COUNTS.lock().unwrap() = Some(counts);
+}
+
+/// The counts set by `record_counts`, if the command reported any
+pub fn take_counts() -> Option<Value> {
+    RUN_COUNTS.lock().unwrap().take()
+}
+
+/// `~/.pynthora/history.ndjson`, creating `~/.pynthora` if needed
+pub fn default_path() -> Result<PathBuf> {
+    let mut path = dirs::home_dir()
+        .ok_or_else(|| Error::Config("Failed to get home directory".to_string()))?;
+    path.push(".pynthora");
+    fs::create_dir_all(&path)?;
+    path.push("history.ndjson");
+    Ok(path)
+}
+
+/// Append `event` to the history file at `path` as one JSON line
+pub fn append(path: &Path, event: &TelemetryEvent) -> Result<()> {
+    let mut line = serde_json::to_vec(event).map_err(std::io::Error::from)?;
+    line.push(b'\n');
+
+    // A single write keeps lines whole when runs finish at the same time
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(&line)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_append_writes_one_record_per_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.ndjson");
+
+        for success in [true, false] {
+            let event = TelemetryEvent::new(RUN_EVENT_TYPE, json!({"success": success}));
+            append(&path, &event).unwrap();
+        }
+
+        let content = fs::read_to_string(&path).unwrap();
+        let records: Vec<TelemetryEvent> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].event_type, RUN_EVENT_TYPE);
+        assert_eq!(records[1].data["success"], false);
+    }
+}
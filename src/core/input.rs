@@ -0,0 +1,677 @@
+//! Event file reader shared by every command that consumes events
+use serde::de::{DeserializeSeed, Deserializer, SeqAccess, Visitor};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, ErrorKind, Read};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use encoding_rs::{Decoder, WINDOWS_1252};
+use flate2::read::MultiGzDecoder;
+use sha2::{Digest, Sha256};
+
+use crate::core::error::{Error, Result};
+
+/// Elements buffered ahead of the consumer when reading a JSON array
+const ARRAY_BUFFER: usize = 1024;
+
+/// Gzip magic bytes, used to detect compressed input regardless of extension
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// UTF-8 byte order mark, which editors on Windows like to prepend
+const UTF8_BOM: [u8; 3] = [0xef, 0xbb, 0xbf];
+
+/// Supported event file layouts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// One JSON event per line
+    Ndjson,
+    /// A single top-level JSON array of events
+    JsonArray,
+    /// CSV with a header row; each record becomes an object keyed by column
+    Csv,
+}
+
+impl FromStr for InputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "ndjson" | "jsonl" => Ok(InputFormat::Ndjson),
+            "json" => Ok(InputFormat::JsonArray),
+            "csv" => Ok(InputFormat::Csv),
+            other => Err(format!("unknown input format '{}' (ndjson, json, csv)", other)),
+        }
+    }
+}
+
+impl fmt::Display for InputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            InputFormat::Ndjson => "ndjson",
+            InputFormat::JsonArray => "json",
+            InputFormat::Csv => "csv",
+        })
+    }
+}
+
+/// Character encoding of an event file; everything is transcoded to UTF-8 before parsing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InputEncoding {
+    /// Strict UTF-8; invalid bytes make the event they're in fail to parse
+    #[default]
+    Utf8,
+    /// ISO-8859-1, decoded as windows-1252 like browsers do
+    Latin1,
+}
+
+impl FromStr for InputEncoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "utf-8" | "utf8" => Ok(InputEncoding::Utf8),
+            "latin1" | "latin-1" | "iso-8859-1" | "windows-1252" | "cp1252" => {
+                Ok(InputEncoding::Latin1)
+            }
+            other => Err(format!("unknown input encoding '{}' (utf-8, latin1)", other)),
+        }
+    }
+}
+
+impl fmt::Display for InputEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            InputEncoding::Utf8 => "utf-8",
+            InputEncoding::Latin1 => "latin1",
+        })
+    }
+}
+
+/// Lazily yields events from a file in any supported format, gzip-compressed or not.
+///
+/// Unparseable events come back as `Error::Decode` (with their line or record
+/// number) and reading continues; `Error::Io` means the file itself can't be read.
+pub struct EventReader {
+    format: InputFormat,
+    inner: Box<dyn Iterator<Item = Result<Value>> + Send>,
+    cleaned: CleanedLines,
+}
+
+impl EventReader {
+    /// Open a UTF-8 file at `path`, picking the format from `hint`, then the extension,
+    /// then the content
+    pub fn open(path: impl AsRef<Path>, hint: Option<InputFormat>) -> Result<Self> {
+        Self::open_with_encoding(path, hint, InputEncoding::Utf8)
+    }
+
+    /// Like [`EventReader::open`], for a file in `encoding`. A leading UTF-8 BOM is
+    /// skipped either way
+    pub fn open_with_encoding(
+        path: impl AsRef<Path>,
+        hint: Option<InputFormat>,
+        encoding: InputEncoding,
+    ) -> Result<Self> {
+        Self::open_file(path.as_ref(), hint, encoding, None)
+    }
+
+    /// Like [`EventReader::open_with_encoding`], also hashing the raw bytes of the file
+    /// (compressed, if it is) as they are read
+    pub fn open_hashed(
+        path: impl AsRef<Path>,
+        hint: Option<InputFormat>,
+        encoding: InputEncoding,
+    ) -> Result<(Self, InputDigest)> {
+        let digest = InputDigest::default();
+        let reader = Self::open_file(path.as_ref(), hint, encoding, Some(digest.clone()))?;
+        Ok((reader, digest))
+    }
+
+    fn open_file(
+        path: &Path,
+        hint: Option<InputFormat>,
+        encoding: InputEncoding,
+        digest: Option<InputDigest>,
+    ) -> Result<Self> {
+        let file = File::open(path)?;
+        let mut reader: Box<dyn BufRead + Send> = match digest {
+            Some(digest) => Box::new(BufReader::new(HashingReader { inner: file, digest })),
+            None => Box::new(BufReader::new(file)),
+        };
+
+        if reader.fill_buf()?.starts_with(&GZIP_MAGIC) {
+            reader = Box::new(BufReader::new(MultiGzDecoder::new(reader)));
+        }
+        if reader.fill_buf()?.starts_with(&UTF8_BOM) {
+            reader.consume(UTF8_BOM.len());
+        }
+        if encoding == InputEncoding::Latin1 {
+            let decoder = WINDOWS_1252.new_decoder_without_bom_handling();
+            reader = Box::new(BufReader::new(Utf8Transcoder::new(reader, decoder)));
+        }
+
+        let format = match hint.or_else(|| format_from_extension(path)) {
+            Some(format) => format,
+            None => sniff_format(&mut reader)?,
+        };
+
+        Ok(Self::from_reader(reader, format))
+    }
+
+    /// Read events in `format` from an already-decompressed reader
+    pub fn from_reader(reader: impl BufRead + Send + 'static, format: InputFormat) -> Self {
+        let cleaned = CleanedLines::default();
+        let inner: Box<dyn Iterator<Item = Result<Value>> + Send> = match format {
+            InputFormat::Ndjson => Box::new(ndjson_events(reader, cleaned.clone())),
+            InputFormat::JsonArray => Box::new(array_events(reader)),
+            InputFormat::Csv => Box::new(csv_events(reader)),
+        };
+        Self {
+            format,
+            inner,
+            cleaned,
+        }
+    }
+
+    /// The format being read
+    pub fn format(&self) -> InputFormat {
+        self.format
+    }
+
+    /// Counter of NDJSON lines that needed cleanup, which keeps counting after the
+    /// reader is consumed
+    pub fn cleaned_lines(&self) -> CleanedLines {
+        self.cleaned.clone()
+    }
+}
+
+/// Number of NDJSON lines that only parsed after removing trailing carriage returns
+/// or a byte order mark, as left by producers on Windows or by concatenating files
+#[derive(Debug, Clone, Default)]
+pub struct CleanedLines(Arc<AtomicUsize>);
+
+impl CleanedLines {
+    pub fn count(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl Iterator for EventReader {
+    type Item = Result<Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+/// Files matching `pattern`, sorted, for reading many files in one run. Only the
+/// file name may use wildcards: `*` for any run of characters and `?` for any one;
+/// like a shell, they don't match a leading `.`. A pattern without wildcards is
+/// returned as is, leaving a missing file to be reported when it is opened
+pub fn expand_pattern(pattern: &str) -> Result<Vec<String>> {
+    let path = Path::new(pattern);
+    let name = match path.file_name().and_then(|name| name.to_str()) {
+        Some(name) if name.contains(['*', '?']) => name,
+        _ => return Ok(vec![pattern.to_string()]),
+    };
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        // A name that isn't UTF-8 can't be matched against the pattern
+        let Some(file_name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        let hidden = file_name.starts_with('.') && !name.starts_with('.');
+        if !hidden && wildcard_match(name, &file_name) && entry.path().is_file() {
+            files.push(path.with_file_name(file_name).to_string_lossy().into_owned());
+        }
+    }
+    if files.is_empty() {
+        return Err(Error::NoEvents(format!("{} (no files match)", pattern)));
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Whether `name` matches `pattern`, where `*` matches any run of characters and
+/// `?` any single one
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Where to resume after the last `*` when the characters after it stop matching
+    let mut backtrack = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                p += 1;
+                backtrack = Some((p, n));
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_n)) => {
+                    p = star_p;
+                    n = star_n + 1;
+                    backtrack = Some((star_p, n));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn format_from_extension(path: &Path) -> Option<InputFormat> {
+    let name = path.file_name()?.to_str()?;
+    let name = name.strip_suffix(".gz").unwrap_or(name);
+
+    match name.rsplit_once('.')?.1 {
+        "ndjson" | "jsonl" => Some(InputFormat::Ndjson),
+        "csv" => Some(InputFormat::Csv),
+        // `.json` is used for both layouts, so let the content decide
+        _ => None,
+    }
+}
+
+/// A leading `[` means a JSON array; anything else is treated as NDJSON
+fn sniff_format(reader: &mut impl BufRead) -> Result<InputFormat> {
+    loop {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            return Ok(InputFormat::Ndjson);
+        }
+
+        match buf.iter().position(|b| !b.is_ascii_whitespace()) {
+            Some(pos) => {
+                let is_array = buf[pos] == b'[';
+                reader.consume(pos);
+                return Ok(if is_array {
+                    InputFormat::JsonArray
+                } else {
+                    InputFormat::Ndjson
+                });
+            }
+            None => {
+                let len = buf.len();
+                reader.consume(len);
+            }
+        }
+    }
+}
+
+/// Events from NDJSON, one per line. `lines` already accepts `\r\n` and a missing
+/// final newline; extra `\r`s at the end of a line (`\r\r\n`, a file ending in
+/// `\r`) and a BOM at its start are dropped. A `\r` inside a line is left to the
+/// JSON parser, which treats it as whitespace between tokens
+fn ndjson_events(
+    reader: impl BufRead,
+    cleaned: CleanedLines,
+) -> impl Iterator<Item = Result<Value>> {
+    reader
+        .lines()
+        .enumerate()
+        .filter_map(move |(idx, line)| match line {
+            // The bad line has been consumed, so carry on like any other parse error
+            Err(e) if e.kind() == ErrorKind::InvalidData => Some(Err(Error::Decode(format!(
+                "line {}: not valid UTF-8 (set the input encoding if the file is latin-1)",
+                idx + 1
+            )))),
+            Err(e) => Some(Err(Error::Io(e))),
+            Ok(line) => {
+                let trimmed = line
+                    .strip_prefix('\u{feff}')
+                    .unwrap_or(&line)
+                    .trim_end_matches('\r');
+                if trimmed.len() != line.len() {
+                    cleaned.0.fetch_add(1, Ordering::Relaxed);
+                }
+                if trimmed.trim().is_empty() {
+                    return None;
+                }
+                Some(
+                    serde_json::from_str(trimmed)
+                        .map_err(|e| Error::Decode(format!("line {}: {}", idx + 1, e))),
+                )
+            }
+        })
+}
+
+/// Running SHA-256 of an input file's raw bytes, filled in by the reader as it goes
+#[derive(Debug, Clone, Default)]
+pub struct InputDigest {
+    state: Arc<Mutex<DigestState>>,
+}
+
+#[derive(Debug, Default)]
+struct DigestState {
+    hasher: Sha256,
+    bytes: u64,
+    complete: bool,
+}
+
+impl InputDigest {
+    /// Hex SHA-256 of the whole file; `None` until it has been read to the end
+    pub fn sha256(&self) -> Option<String> {
+        let state = self.state.lock().unwrap();
+        state
+            .complete
+            .then(|| hex::encode(state.hasher.clone().finalize()))
+    }
+
+    /// Raw bytes read so far
+    pub fn bytes(&self) -> u64 {
+        self.state.lock().unwrap().bytes
+    }
+}
+
+/// Feeds everything read from `inner` into a digest
+struct HashingReader<R> {
+    inner: R,
+    digest: InputDigest,
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        let mut state = self.digest.state.lock().unwrap();
+        if n == 0 && !buf.is_empty() {
+            state.complete = true;
+        }
+        state.hasher.update(&buf[..n]);
+        state.bytes += n as u64;
+        Ok(n)
+    }
+}
+
+/// Decodes a byte stream in some other encoding into UTF-8 as it's read
+struct Utf8Transcoder<R> {
+    inner: R,
+    decoder: Decoder,
+    out: Vec<u8>,
+    pos: usize,
+    done: bool,
+}
+
+impl<R: BufRead> Utf8Transcoder<R> {
+    fn new(inner: R, decoder: Decoder) -> Self {
+        Self {
+            inner,
+            decoder,
+            out: Vec::new(),
+            pos: 0,
+            done: false,
+        }
+    }
+
+    /// Decode the next chunk of input into `out`; false once the input is exhausted
+    fn refill(&mut self) -> std::io::Result<bool> {
+        while !self.done {
+            let input = self.inner.fill_buf()?;
+            let last = input.is_empty();
+            let capacity = self
+                .decoder
+                .max_utf8_buffer_length(input.len())
+                .ok_or_else(|| std::io::Error::new(ErrorKind::OutOfMemory, "chunk too large"))?;
+
+            self.out.resize(capacity, 0);
+            let (_, read, written, _) = self.decoder.decode_to_utf8(input, &mut self.out, last);
+            self.inner.consume(read);
+            self.out.truncate(written);
+            self.pos = 0;
+            self.done = last;
+
+            if written > 0 {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+impl<R: BufRead> Read for Utf8Transcoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.pos == self.out.len() && !self.refill()? {
+            return Ok(0);
+        }
+        let n = buf.len().min(self.out.len() - self.pos);
+        buf[..n].copy_from_slice(&self.out[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+fn csv_events(reader: impl Read) -> impl Iterator<Item = Result<Value>> {
+    let mut csv = csv::Reader::from_reader(reader);
+    let headers = csv.headers().cloned();
+
+    let mut records = csv.into_records().enumerate();
+    let mut header_error = headers.as_ref().err().map(|e| e.to_string());
+
+    std::iter::from_fn(move || {
+        if let Some(e) = header_error.take() {
+            return Some(Err(Error::Decode(format!("CSV header: {}", e))));
+        }
+        let headers = headers.as_ref().ok()?;
+
+        let (idx, record) = records.next()?;
+        Some(match record {
+            Ok(record) => {
+                let event: Map<String, Value> = headers
+                    .iter()
+                    .zip(record.iter())
+                    .map(|(key, field)| (key.to_string(), csv_value(field)))
+                    .collect();
+                Ok(Value::Object(event))
+            }
+            // Record 1 is the first row after the header, i.e. line 2
+            Err(e) => Err(Error::Decode(format!("line {}: {}", idx + 2, e))),
+        })
+    })
+}
+
+/// Numbers and booleans keep their JSON type; everything else stays a string
+fn csv_value(field: &str) -> Value {
+    if let Ok(n) = field.parse::<i64>() {
+        return Value::from(n);
+    }
+    if let Ok(n) = field.parse::<f64>() {
+        return Value::from(n);
+    }
+    match field {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => Value::String(field.to_string()),
+    }
+}
+
+/// Stream the elements of a top-level JSON array without holding the whole array.
+///
+/// serde_json has no pull API for array elements, so a thread deserializes the array
+/// and hands elements over a bounded channel.
+fn array_events(reader: impl Read + Send + 'static) -> impl Iterator<Item = Result<Value>> {
+    let (tx, rx) = sync_channel(ARRAY_BUFFER);
+
+    thread::spawn(move || {
+        let mut deserializer = serde_json::Deserializer::from_reader(reader);
+        let result = ArraySeed { tx: &tx }.deserialize(&mut deserializer);
+        if let Err(e) = result.and_then(|_| deserializer.end()) {
+            // The consumer may already be gone; nothing left to report to
+            let _ = tx.send(Err(Error::Decode(format!("JSON array: {}", e))));
+        }
+    });
+
+    ArrayEvents { rx }
+}
+
+struct ArrayEvents {
+    rx: Receiver<Result<Value>>,
+}
+
+impl Iterator for ArrayEvents {
+    type Item = Result<Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rx.recv().ok()
+    }
+}
+
+struct ArraySeed<'a> {
+    tx: &'a SyncSender<Result<Value>>,
+}
+
+impl<'de> DeserializeSeed<'de> for ArraySeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> std::result::Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ArraySeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a JSON array of events")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<(), A::Error> {
+        while let Some(event) = seq.next_element::<Value>()? {
+            if self.tx.send(Ok(event)).is_err() {
+                // Consumer stopped early; abandon the rest of the array
+                return Err(serde::de::Error::custom("reader dropped"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+
+    fn read_all(content: &str, format: InputFormat) -> Vec<Result<Value>> {
+        EventReader::from_reader(Cursor::new(content.to_string()), format).collect()
+    }
+
+    #[test]
+    fn test_ndjson_skips_blank_lines_and_reports_line_numbers() {
+        let events = read_all("{\"a\":1}\n\nnot json\n{\"a\":2}\n", InputFormat::Ndjson);
+        assert_eq!(events.len(), 3);
+        assert!(matches!(&events[1], Err(Error::Decode(msg)) if msg.starts_with("line 3")));
+        assert_eq!(events[2].as_ref().unwrap()["a"], 2);
+    }
+
+    #[test]
+    fn test_ndjson_cleans_trailing_carriage_returns() {
+        let input = "{\"a\":1}\r\r\n\u{feff}{\"a\":2}\n{\"a\":\r3}\r\n{\"a\":4}\r";
+        let reader = EventReader::from_reader(Cursor::new(input), InputFormat::Ndjson);
+        let cleaned = reader.cleaned_lines();
+
+        let events: Vec<Value> = reader.map(|e| e.unwrap()).collect();
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[2]["a"], 3, "a \\r inside a line is JSON whitespace");
+        assert_eq!(cleaned.count(), 3, "plain \\r\\n endings don't count");
+    }
+
+    #[test]
+    fn test_json_array_yields_elements() {
+        let events = read_all("[{\"a\":1}, {\"a\":2}]", InputFormat::JsonArray);
+        let values: Vec<_> = events.into_iter().map(|e| e.unwrap()["a"].clone()).collect();
+        assert_eq!(values, vec![Value::from(1), Value::from(2)]);
+    }
+
+    #[test]
+    fn test_csv_records_become_objects() {
+        let events = read_all("source,value,ok\nrobot-1,4.5,true\n", InputFormat::Csv);
+        let event = events[0].as_ref().unwrap();
+        assert_eq!(event["source"], "robot-1");
+        assert_eq!(event["value"], 4.5);
+        assert_eq!(event["ok"], true);
+    }
+
+    #[test]
+    fn test_open_detects_gzip_and_array() {
+        let mut file = tempfile::Builder::new().suffix(".json.gz").tempfile().unwrap();
+        let mut encoder =
+            flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(b"  [{\"a\":1}]").unwrap();
+        file.write_all(&encoder.finish().unwrap()).unwrap();
+
+        let reader = EventReader::open(file.path(), None).unwrap();
+        assert_eq!(reader.format(), InputFormat::JsonArray);
+        assert_eq!(reader.count(), 1);
+    }
+
+    #[test]
+    fn test_open_skips_bom_and_transcodes_latin1() {
+        let mut file = tempfile::Builder::new().suffix(".ndjson").tempfile().unwrap();
+        file.write_all(b"\xef\xbb\xbf{\"a\":1}\n").unwrap();
+        let events: Vec<_> = EventReader::open(file.path(), None).unwrap().collect();
+        assert_eq!(events[0].as_ref().unwrap()["a"], 1);
+
+        let mut file = tempfile::Builder::new().suffix(".ndjson").tempfile().unwrap();
+        file.write_all(b"{\"city\":\"M\xfcnchen\"}\n").unwrap();
+        let events: Vec<_> = EventReader::open(file.path(), None).unwrap().collect();
+        assert!(matches!(&events[0], Err(Error::Decode(msg)) if msg.contains("UTF-8")));
+
+        let reader =
+            EventReader::open_with_encoding(file.path(), None, InputEncoding::Latin1).unwrap();
+        let events: Vec<_> = reader.collect();
+        assert_eq!(events[0].as_ref().unwrap()["city"], "München");
+    }
+
+    #[test]
+    fn test_open_hashed_digests_the_raw_file() {
+        let mut file = tempfile::Builder::new().suffix(".ndjson").tempfile().unwrap();
+        file.write_all(b"{\"a\":1}\n{\"a\":2}\n").unwrap();
+
+        let (reader, digest) =
+            EventReader::open_hashed(file.path(), None, InputEncoding::Utf8).unwrap();
+        assert_eq!(digest.sha256(), None, "nothing read yet");
+        assert_eq!(reader.count(), 2);
+        assert_eq!(digest.bytes(), 16);
+        assert_eq!(
+            digest.sha256().unwrap(),
+            hex::encode(Sha256::digest(b"{\"a\":1}\n{\"a\":2}\n"))
+        );
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*.ndjson", "events.ndjson"));
+        assert!(wildcard_match("part-??.csv", "part-07.csv"));
+        assert!(wildcard_match("a*b*c", "aXbYbZc"));
+        assert!(!wildcard_match("*.ndjson", "events.ndjson.gz"));
+        assert!(!wildcard_match("part-?.csv", "part-07.csv"));
+    }
+
+    #[test]
+    fn test_expand_pattern_lists_matching_files_sorted() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["b.ndjson", "a.ndjson", "c.csv", ".hidden.ndjson"] {
+            std::fs::write(dir.path().join(name), "{}\n").unwrap();
+        }
+        std::fs::create_dir(dir.path().join("d.ndjson")).unwrap();
+        let in_dir = |name: &str| dir.path().join(name).to_string_lossy().into_owned();
+
+        let files = expand_pattern(&in_dir("*.ndjson")).unwrap();
+        assert_eq!(files, [in_dir("a.ndjson"), in_dir("b.ndjson")]);
+        assert_eq!(expand_pattern(&in_dir("?.csv")).unwrap(), [in_dir("c.csv")]);
+        assert_eq!(expand_pattern(&in_dir("x.csv")).unwrap(), [in_dir("x.csv")]);
+        assert!(matches!(expand_pattern(&in_dir("*.json")), Err(Error::NoEvents(_))));
+    }
+}
@@ -0,0 +1,98 @@
+//! Success journal: one line per event the gateway accepted, for reconciling a run
+//! against its dead-letter file
+use chrono::{SecondsFormat, Utc};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::core::error::Result;
+use crate::core::sent_log::KEY_POINTER;
+
+/// Appends a JSON line per acknowledged event: its `idempotency_key` (when it has
+/// one), the SHA-256 of its serialized form and when the ack arrived
+pub struct Journal {
+    path: PathBuf,
+    writer: Mutex<BufWriter<File>>,
+    written: AtomicUsize,
+}
+
+impl Journal {
+    /// Open `path` for appending, creating it if needed
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Self {
+            path,
+            writer: Mutex::new(BufWriter::new(file)),
+            written: AtomicUsize::new(0),
+        })
+    }
+
+    /// Record `events` as acknowledged now, flushing so a crash right after doesn't
+    /// lose them
+    pub fn record(&self, events: &[Value]) -> Result<()> {
+        let acked_at = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+        let mut writer = self.writer.lock().unwrap();
+        for event in events {
+            serde_json::to_writer(&mut *writer, &entry(event, &acked_at))
+                .map_err(std::io::Error::from)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+
+        self.written.fetch_add(events.len(), Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Events recorded by this run
+    pub fn written(&self) -> usize {
+        self.written.load(Ordering::Relaxed)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+fn entry(event: &Value, acked_at: &str) -> Value {
+    let sha256 = hex::encode(Sha256::digest(event.to_string().as_bytes()));
+    let mut entry = json!({"sha256": sha256, "acked_at": acked_at});
+    if let Some(key) = event.pointer(KEY_POINTER).and_then(Value::as_str) {
+        entry["idempotency_key"] = Value::String(key.to_string());
+    }
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_appends_one_line_per_event() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let keyed = json!({"metadata": {"idempotency_key": "a1"}, "data": {}});
+        let plain = json!({"data": {"n": 1}});
+
+        let journal = Journal::open(file.path()).unwrap();
+        journal.record(&[keyed, plain.clone()]).unwrap();
+        assert_eq!(journal.written(), 2);
+
+        let content = std::fs::read_to_string(file.path()).unwrap();
+        let lines: Vec<Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["idempotency_key"], "a1");
+        assert!(lines[1].get("idempotency_key").is_none());
+        assert_eq!(
+            lines[1]["sha256"],
+            hex::encode(Sha256::digest(plain.to_string().as_bytes()))
+        );
+        assert!(lines[1]["acked_at"].as_str().unwrap().ends_with('Z'));
+    }
+}
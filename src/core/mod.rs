@@ -1,6 +1,16 @@
 pub mod config;
+pub mod dead_letter;
+pub mod error;
+pub mod history;
+pub mod input;
+pub mod journal;
 pub mod logger;
+pub mod projection;
 pub mod retry;
+pub mod schema;
+pub mod sent_log;
 pub mod telemetry;
+pub mod tls;
 pub mod validation;
+pub mod workspace;
 
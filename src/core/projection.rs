@@ -0,0 +1,139 @@
+//! Field pruning applied to events before they are validated and sent
+use serde_json::{Map, Value};
+
+use crate::core::error::{Error, Result};
+use crate::core::validation::REQUIRED_EVENT_FIELDS;
+
+/// Fields to remove from, or keep in, each event. Paths are dotted (`data.email`)
+/// and reach into nested objects.
+#[derive(Debug, Clone, Default)]
+pub struct Projection {
+    drop: Vec<Vec<String>>,
+    keep: Vec<Vec<String>>,
+}
+
+impl Projection {
+    /// Build a projection, refusing one that would strip a required event field
+    pub fn new(drop: &[String], keep: &[String]) -> Result<Self> {
+        let drop: Vec<Vec<String>> = drop.iter().map(|path| split_path(path)).collect();
+        let keep: Vec<Vec<String>> = keep.iter().map(|path| split_path(path)).collect();
+
+        for field in REQUIRED_EVENT_FIELDS {
+            if drop.iter().any(|path| path.len() == 1 && path[0] == *field) {
+                return Err(Error::Validation(format!(
+                    "--drop-field would remove required field '{}'",
+                    field
+                )));
+            }
+            if !keep.is_empty() && !keep.iter().any(|path| path[0] == *field) {
+                return Err(Error::Validation(format!(
+                    "--keep-only must include required field '{}'",
+                    field
+                )));
+            }
+        }
+
+        Ok(Self { drop, keep })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.drop.is_empty() && self.keep.is_empty()
+    }
+
+    /// Prune `event` in place; events that aren't objects are left as they are
+    pub fn apply(&self, event: &mut Value) {
+        let Value::Object(obj) = event else {
+            return;
+        };
+
+        if !self.keep.is_empty() {
+            let mut kept = Map::new();
+            for path in &self.keep {
+                if let Some(value) = take_path(obj, path) {
+                    insert_path(&mut kept, path, value);
+                }
+            }
+            *obj = kept;
+        }
+
+        for path in &self.drop {
+            take_path(obj, path);
+        }
+    }
+}
+
+fn split_path(path: &str) -> Vec<String> {
+    path.split('.').map(str::to_string).collect()
+}
+
+/// Remove and return the value at `path`
+fn take_path(obj: &mut Map<String, Value>, path: &[String]) -> Option<Value> {
+    match path {
+        [] => None,
+        [field] => obj.remove(field),
+        [field, rest @ ..] => match obj.get_mut(field) {
+            Some(Value::Object(inner)) => take_path(inner, rest),
+            _ => None,
+        },
+    }
+}
+
+/// Set the value at `path`, creating intermediate objects
+fn insert_path(obj: &mut Map<String, Value>, path: &[String], value: Value) {
+    match path {
+        [] => {}
+        [field] => {
+            obj.insert(field.clone(), value);
+        }
+        [field, rest @ ..] => {
+            let inner = obj
+                .entry(field.clone())
+                .or_insert_with(|| Value::Object(Map::new()));
+            if let Value::Object(inner) = inner {
+                insert_path(inner, rest, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn paths(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn test_drop_and_keep_fields() {
+        let event = json!({
+            "timestamp": 1,
+            "source": "robot-1",
+            "data": {"email": "a@example.com", "reading": 4.2, "gps": {"lat": 1, "lon": 2}},
+            "user": "alice",
+        });
+
+        let projection = Projection::new(&paths(&["user", "data.email", "data.gps.lat"]), &[])
+            .unwrap();
+        let mut dropped = event.clone();
+        projection.apply(&mut dropped);
+        assert_eq!(
+            dropped,
+            json!({"timestamp": 1, "source": "robot-1", "data": {"reading": 4.2, "gps": {"lon": 2}}})
+        );
+
+        let projection =
+            Projection::new(&[], &paths(&["timestamp", "source", "data.reading"])).unwrap();
+        let mut kept = event;
+        projection.apply(&mut kept);
+        assert_eq!(kept, json!({"timestamp": 1, "source": "robot-1", "data": {"reading": 4.2}}));
+    }
+
+    #[test]
+    fn test_refuses_to_drop_required_fields() {
+        assert!(Projection::new(&paths(&["source"]), &[]).is_err());
+        assert!(Projection::new(&[], &paths(&["timestamp", "data"])).is_err());
+        assert!(Projection::new(&paths(&["data.email"]), &[]).is_ok());
+    }
+}
@@ -9,7 +9,7 @@ pub struct RetryConfig {
     pub max_attempts: u32,
     pub initial_delay: Duration,
     pub max_delay: Duration,
-    pub backoff_multiplier: f64,
+    pub strategy: BackoffStrategy,
 }
 
 impl Default for RetryConfig {
@@ -18,22 +18,71 @@ impl Default for RetryConfig {
             max_attempts: 3,
             initial_delay: Duration::from_millis(100),
             max_delay: Duration::from_secs(30),
-            backoff_multiplier: 2.0,
+            strategy: BackoffStrategy::default(),
         }
     }
 }
 
-/// Retry a function with exponential backoff
+impl RetryConfig {
+    /// How long to wait before the `retry`-th retry (1 for the first), capped at `max_delay`
+    pub fn delay_for(&self, retry: u32) -> Duration {
+        let initial = self.initial_delay.as_millis() as f64;
+        let steps = retry.saturating_sub(1);
+        let millis = match self.strategy {
+            BackoffStrategy::Fixed => initial,
+            BackoffStrategy::Linear => initial * f64::from(steps + 1),
+            BackoffStrategy::Exponential { multiplier } => {
+                initial * multiplier.powi(steps.min(i32::MAX as u32) as i32)
+            }
+        };
+        let cap = self.max_delay.as_millis() as f64;
+        Duration::from_millis(millis.min(cap) as u64)
+    }
+}
+
+/// How the delay grows between retries
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BackoffStrategy {
+    /// Wait `initial_delay` every time
+    Fixed,
+    /// Wait `initial_delay` times the retry number
+    Linear,
+    /// Multiply the delay by `multiplier` after each retry
+    Exponential { multiplier: f64 },
+}
+
+impl Default for BackoffStrategy {
+    fn default() -> Self {
+        BackoffStrategy::Exponential { multiplier: 2.0 }
+    }
+}
+
+/// Retry a function, backing off between attempts as `config.strategy` says
 pub async fn retry_with_backoff<F, Fut, T, E>(
+    config: &RetryConfig,
+    f: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = std::result::Result<T, E>>,
+    E: std::fmt::Display,
+{
+    retry_with_backoff_notify(config, f, |_, _| {}).await
+}
+
+/// Retry a function with backoff, calling `on_retry(attempt, delay)`
+/// before each retry with the number of the attempt about to be made
+pub async fn retry_with_backoff_notify<F, Fut, T, E, R>(
     config: &RetryConfig,
     mut f: F,
+    mut on_retry: R,
 ) -> Result<T>
 where
     F: FnMut() -> Fut,
     Fut: std::future::Future<Output = std::result::Result<T, E>>,
     E: std::fmt::Display,
+    R: FnMut(u32, Duration),
 {
-    let mut delay = config.initial_delay;
     let mut last_error = None;
 
     for attempt in 1..=config.max_attempts {
@@ -47,15 +96,13 @@ where
             Err(e) => {
                 last_error = Some(e.to_string());
                 if attempt < config.max_attempts {
+                    let delay = config.delay_for(attempt);
                     warn!(
                         "Attempt {} failed, retrying in {:?}...",
                         attempt, delay
                     );
+                    on_retry(attempt + 1, delay);
                     sleep(delay).await;
-                    delay = Duration::from_millis(
-                        (delay.as_millis() as f64 * config.backoff_multiplier) as u64,
-                    );
-                    delay = delay.min(config.max_delay);
                 } else {
                     warn!("All {} attempts failed", config.max_attempts);
                 }
@@ -70,6 +117,50 @@ where
     )
 }
 
+/// Retry with backoff, but return immediately on errors `should_retry`
+/// rejects. Unlike `retry_with_backoff`, the caller gets the original error back.
+pub async fn retry_with_backoff_if<F, Fut, T, E, P>(
+    config: &RetryConfig,
+    mut f: F,
+    should_retry: P,
+) -> std::result::Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = std::result::Result<T, E>>,
+    E: std::fmt::Display,
+    P: Fn(&E) -> bool,
+{
+    let mut attempt = 1;
+
+    loop {
+        match f().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < config.max_attempts && should_retry(&e) => {
+                let delay = config.delay_for(attempt);
+                warn!("Attempt {} failed ({}), retrying in {:?}...", attempt, e, delay);
+                sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Whether an IO error is likely transient (interrupted calls, flaky network mounts)
+/// rather than permanent like a missing file or denied permission
+pub fn is_retryable_io_error(error: &std::io::Error) -> bool {
+    use std::io::ErrorKind;
+
+    matches!(
+        error.kind(),
+        ErrorKind::Interrupted
+            | ErrorKind::WouldBlock
+            | ErrorKind::TimedOut
+            | ErrorKind::ResourceBusy
+            | ErrorKind::StaleNetworkFileHandle
+    )
+}
+
 /// Check if an error is retryable
 pub fn is_retryable_error(error: &str) -> bool {
     let retryable_patterns = [
@@ -87,3 +178,35 @@ pub fn is_retryable_error(error: &str) -> bool {
     retryable_patterns.iter().any(|pattern| error_lower.contains(pattern))
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delays(strategy: BackoffStrategy) -> Vec<u128> {
+        let config = RetryConfig {
+            max_attempts: 6,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(1000),
+            strategy,
+        };
+        (1..=5).map(|retry| config.delay_for(retry).as_millis()).collect()
+    }
+
+    #[test]
+    fn test_delay_sequence_per_strategy() {
+        assert_eq!(delays(BackoffStrategy::Fixed), [100, 100, 100, 100, 100]);
+        assert_eq!(delays(BackoffStrategy::Linear), [100, 200, 300, 400, 500]);
+        assert_eq!(delays(BackoffStrategy::default()), [100, 200, 400, 800, 1000]);
+        assert_eq!(
+            delays(BackoffStrategy::Exponential { multiplier: 3.0 }),
+            [100, 300, 900, 1000, 1000]
+        );
+    }
+
+    #[test]
+    fn test_delay_is_capped_for_late_retries() {
+        let config = RetryConfig::default();
+        assert_eq!(config.delay_for(u32::MAX), config.max_delay);
+    }
+}
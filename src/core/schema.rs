@@ -0,0 +1,94 @@
+//! JSON Schema checks for events, on top of the built-in `validate_event` rules
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+use crate::core::error::{Error, Result};
+
+/// A compiled JSON Schema that events are checked against before sending
+pub struct EventSchema {
+    validator: jsonschema::Validator,
+}
+
+impl EventSchema {
+    /// Compile `schema`, failing with `Error::Validation` if it isn't a valid JSON Schema
+    pub fn new(schema: &Value) -> Result<Self> {
+        let validator = jsonschema::validator_for(schema)
+            .map_err(|e| Error::Validation(format!("invalid JSON Schema: {}", e)))?;
+        Ok(Self { validator })
+    }
+
+    /// Load and compile a schema from a JSON or YAML file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let is_yaml = matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some("yaml" | "yml")
+        );
+        let schema: Value = if is_yaml {
+            serde_yaml::from_str(&content)
+                .map_err(|e| Error::Decode(format!("schema {}: {}", path.display(), e)))?
+        } else {
+            serde_json::from_str(&content)
+                .map_err(|e| Error::Decode(format!("schema {}: {}", path.display(), e)))?
+        };
+
+        Self::new(&schema).map_err(|e| match e {
+            Error::Validation(msg) => Error::Validation(format!("{}: {}", path.display(), msg)),
+            other => other,
+        })
+    }
+
+    /// Every way `event` breaks the schema, as `<JSON pointer>: <message>`; empty when
+    /// the event conforms
+    pub fn violations(&self, event: &Value) -> Vec<String> {
+        self.validator
+            .iter_errors(event)
+            .map(|error| {
+                let path = match error.instance_path.as_str() {
+                    "" => "/",
+                    path => path,
+                };
+                format!("{}: {}", path, error)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_violations_name_the_failing_path() {
+        let schema = EventSchema::new(&json!({
+            "type": "object",
+            "required": ["data"],
+            "properties": {
+                "data": {
+                    "type": "object",
+                    "properties": {"temperature": {"type": "number", "maximum": 100}}
+                }
+            }
+        }))
+        .unwrap();
+
+        assert!(schema.violations(&json!({"data": {"temperature": 21.5}})).is_empty());
+
+        let violations = schema.violations(&json!({"data": {"temperature": "hot"}}));
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].starts_with("/data/temperature: "), "{}", violations[0]);
+
+        let violations = schema.violations(&json!({"source": "s"}));
+        assert!(violations[0].starts_with("/: "), "{}", violations[0]);
+    }
+
+    #[test]
+    fn test_invalid_schema_is_rejected() {
+        assert!(matches!(
+            EventSchema::new(&json!({"type": "not-a-type"})),
+            Err(Error::Validation(_))
+        ));
+    }
+}
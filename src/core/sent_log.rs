@@ -0,0 +1,135 @@
+//! Local record of events delivered under a `--run-id`, so running the same
+//! command twice doesn't send them twice
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::core::error::{Error, Result};
+
+/// Where the key of an event is read from; `stream --run-id` derives it from content
+pub const KEY_POINTER: &str = "/metadata/idempotency_key";
+
+/// Idempotency keys already delivered, backed by an append-only file with one key
+/// per line.
+///
+/// Keys are recorded only once the gateway has accepted their batch, so events
+/// that failed or were interrupted are sent again on the next run.
+pub struct SentLog {
+    path: PathBuf,
+    sent: HashSet<String>,
+    writer: Mutex<BufWriter<File>>,
+}
+
+impl SentLog {
+    /// Open the log for `run_id` under `~/.pynthora/runs`
+    pub fn for_run(run_id: &str) -> Result<Self> {
+        let valid = !run_id.is_empty()
+            && run_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            && !run_id.starts_with('.');
+        if !valid {
+            return Err(Error::Validation(format!(
+                "run id '{}' may only contain letters, digits, '-', '_' and '.'",
+                run_id
+            )));
+        }
+
+        let mut path = dirs::home_dir()
+            .ok_or_else(|| Error::Config("Failed to get home directory".to_string()))?;
+        path.push(".pynthora");
+        path.push("runs");
+        fs::create_dir_all(&path)?;
+        path.push(format!("{}.keys", run_id));
+        Self::open(path)
+    }
+
+    /// Load the keys already in `path` and open it for appending, creating it if needed
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)?;
+
+        let mut sent = HashSet::new();
+        for line in BufReader::new(&file).lines() {
+            let line = line?;
+            if !line.is_empty() {
+                sent.insert(line);
+            }
+        }
+
+        Ok(Self {
+            path,
+            sent,
+            writer: Mutex::new(BufWriter::new(file)),
+        })
+    }
+
+    /// Whether `event` was delivered by an earlier run; events without a key never were
+    pub fn contains(&self, event: &Value) -> bool {
+        key(event).is_some_and(|key| self.sent.contains(key))
+    }
+
+    /// Record delivered events, flushing so a crash right after doesn't lose them
+    pub fn record(&self, events: &[Value]) -> Result<()> {
+        let mut writer = self.writer.lock().unwrap();
+        for key in events.iter().filter_map(key) {
+            writeln!(writer, "{}", key)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Keys recorded before this run
+    pub fn len(&self) -> usize {
+        self.sent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sent.is_empty()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+fn key(event: &Value) -> Option<&str> {
+    event.pointer(KEY_POINTER).and_then(Value::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_recorded_keys_survive_reopening() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let first = json!({"metadata": {"idempotency_key": "a1"}});
+        let second = json!({"metadata": {"idempotency_key": "b2"}});
+
+        let log = SentLog::open(file.path()).unwrap();
+        assert!(log.is_empty());
+        log.record(std::slice::from_ref(&first)).unwrap();
+        assert!(!log.contains(&first), "keys count from the next run on");
+
+        let log = SentLog::open(file.path()).unwrap();
+        assert_eq!(log.len(), 1);
+        assert!(log.contains(&first));
+        assert!(!log.contains(&second));
+        assert!(!log.contains(&json!({"data": {}})));
+    }
+
+    #[test]
+    fn test_run_id_must_be_a_file_name() {
+        assert!(SentLog::for_run("../etc/passwd").is_err());
+        assert!(SentLog::for_run("").is_err());
+    }
+}
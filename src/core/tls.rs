@@ -0,0 +1,97 @@
+//! TLS settings shared by the HTTP and WebSocket clients
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+use tracing::warn;
+
+use crate::core::config::Config;
+
+/// TLS settings derived from `Config`, with PEM files already read
+#[derive(Debug, Clone, Default)]
+pub struct TlsSettings {
+    /// Extra root CA (e.g. a private CA for self-hosted gateways)
+    pub ca_cert: Option<Vec<u8>>,
+    /// Client certificate and PKCS#8 private key for mutual TLS
+    pub client_identity: Option<(Vec<u8>, Vec<u8>)>,
+    /// Skip certificate verification (local dev only)
+    pub accept_invalid_certs: bool,
+}
+
+impl TlsSettings {
+    /// Read the CA and client certificate files referenced by the config
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let ca_cert = config.ca_cert_path.as_deref().map(read_pem).transpose()?;
+
+        let client_identity = match (&config.client_cert_path, &config.client_key_path) {
+            (Some(cert), Some(key)) => Some((read_pem(cert)?, read_pem(key)?)),
+            (None, None) => None,
+            _ => anyhow::bail!("client_cert_path and client_key_path must be set together"),
+        };
+
+        if config.danger_accept_invalid_certs {
+            warn!("TLS certificate verification is disabled");
+            eprintln!(
+                "WARNING: danger_accept_invalid_certs is set; TLS certificates are NOT verified. \
+                 Use this only with local development gateways."
+            );
+        }
+
+        Ok(Self {
+            ca_cert,
+            client_identity,
+            accept_invalid_certs: config.danger_accept_invalid_certs,
+        })
+    }
+
+    /// Apply the settings to a reqwest (rustls) client builder
+    pub fn apply_to_http(
+        &self,
+        mut builder: reqwest::ClientBuilder,
+    ) -> Result<reqwest::ClientBuilder> {
+        if let Some(ca_cert) = &self.ca_cert {
+            let cert = reqwest::Certificate::from_pem(ca_cert).context("Invalid CA certificate")?;
+            builder = builder.add_root_certificate(cert);
+        }
+
+        if self.accept_invalid_certs {
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+
+        if let Some((cert, key)) = &self.client_identity {
+            let pem = [cert.as_slice(), b"\n", key.as_slice()].concat();
+            let identity =
+                reqwest::Identity::from_pem(&pem).context("Invalid client certificate or key")?;
+            builder = builder.identity(identity);
+        }
+
+        Ok(builder)
+    }
+
+    /// Build a native-tls connector for the WebSocket client, or `None` for the defaults
+    pub fn native_tls_connector(&self) -> Result<Option<native_tls::TlsConnector>> {
+        if self.ca_cert.is_none() && self.client_identity.is_none() && !self.accept_invalid_certs {
+            return Ok(None);
+        }
+
+        let mut builder = native_tls::TlsConnector::builder();
+        builder.danger_accept_invalid_certs(self.accept_invalid_certs);
+
+        if let Some(ca_cert) = &self.ca_cert {
+            let cert =
+                native_tls::Certificate::from_pem(ca_cert).context("Invalid CA certificate")?;
+            builder.add_root_certificate(cert);
+        }
+
+        if let Some((cert, key)) = &self.client_identity {
+            let identity = native_tls::Identity::from_pkcs8(cert, key)
+                .context("Invalid client certificate or key")?;
+            builder.identity(identity);
+        }
+
+        Ok(Some(builder.build().context("Failed to build TLS connector")?))
+    }
+}
+
+fn read_pem(path: &Path) -> Result<Vec<u8>> {
+    fs::read(path).with_context(|| format!("Failed to read certificate: {}", path.display()))
+}
@@ -1,10 +1,16 @@
 use anyhow::{Context, Result};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use tracing::debug;
 
+/// Batches at least this large are validated across threads; smaller ones (like the
+/// per-request batches formed while streaming) aren't worth the hand-off
+const PARALLEL_THRESHOLD: usize = 1024;
+
 /// Pipeline validation result
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ValidationResult {
     pub is_valid: bool,
     pub errors: Vec<String>,
@@ -28,6 +34,35 @@ impl ValidationResult {
     pub fn add_warning(&mut self, warning: String) {
         self.warnings.push(warning);
     }
+
+    /// Promote all warnings to errors, for `--strict` checks in CI
+    pub fn into_strict(mut self) -> Self {
+        self.errors.append(&mut self.warnings);
+        self.is_valid = self.errors.is_empty();
+        self
+    }
+}
+
+/// Size limits applied when validating a batch of events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BatchLimits {
+    /// Hard cap on events per batch (unlimited when unset)
+    pub max_events: Option<usize>,
+    /// Batches larger than this produce a warning
+    pub recommended_max: usize,
+    /// Hard cap on the serialized size of a batch in bytes (unlimited when unset)
+    pub max_total_bytes: Option<usize>,
+}
+
+impl Default for BatchLimits {
+    fn default() -> Self {
+        Self {
+            max_events: None,
+            recommended_max: 1000,
+            max_total_bytes: None,
+        }
+    }
 }
 
 /// Validate pipeline definition
@@ -100,6 +135,9 @@ pub fn validate_pipeline(pipeline: &Value) -> ValidationResult {
     result
 }
 
+/// Top-level fields every event must have
+pub const REQUIRED_EVENT_FIELDS: &[&str] = &["timestamp", "source", "data"];
+
 /// Validate telemetry event
 pub fn validate_event(event: &Value) -> ValidationResult {
     let mut result = ValidationResult::new();
@@ -111,26 +149,17 @@ pub fn validate_event(event: &Value) -> ValidationResult {
 
     let obj = event.as_object().unwrap();
 
-    // Required: timestamp
-    if !obj.contains_key("timestamp") {
-        result.add_error("Event must have a 'timestamp' field".to_string());
-    }
-
-    // Required: source
-    if !obj.contains_key("source") {
-        result.add_error("Event must have a 'source' field".to_string());
-    }
-
-    // Required: data
-    if !obj.contains_key("data") {
-        result.add_error("Event must have a 'data' field".to_string());
+    for field in REQUIRED_EVENT_FIELDS {
+        if !obj.contains_key(*field) {
+            result.add_error(format!("Event must have a '{}' field", field));
+        }
     }
 
     result
 }
 
 /// Validate batch of events
-pub fn validate_batch(events: &[Value]) -> ValidationResult {
+pub fn validate_batch(events: &[Value], limits: &BatchLimits) -> ValidationResult {
     let mut result = ValidationResult::new();
 
     if events.is_empty() {
@@ -138,15 +167,49 @@ pub fn validate_batch(events: &[Value]) -> ValidationResult {
         return result;
     }
 
-    if events.len() > 1000 {
+    if let Some(max_events) = limits.max_events {
+        if events.len() > max_events {
+            result.add_error(format!(
+                "Batch size {} exceeds limit of {} events",
+                events.len(),
+                max_events
+            ));
+        }
+    }
+
+    if events.len() > limits.recommended_max {
         result.add_warning(format!(
-            "Large batch size: {} events (recommended: < 1000)",
-            events.len()
+            "Large batch size: {} events (recommended: < {})",
+            events.len(),
+            limits.recommended_max
         ));
     }
 
-    for (idx, event) in events.iter().enumerate() {
-        let event_result = validate_event(event);
+    let parallel = events.len() >= PARALLEL_THRESHOLD;
+
+    if let Some(max_bytes) = limits.max_total_bytes {
+        let event_bytes = |e: &Value| e.to_string().len();
+        let total_bytes: usize = if parallel {
+            events.par_iter().map(event_bytes).sum()
+        } else {
+            events.iter().map(event_bytes).sum()
+        };
+        if total_bytes > max_bytes {
+            result.add_error(format!(
+                "Batch payload of {} bytes exceeds limit of {} bytes",
+                total_bytes, max_bytes
+            ));
+        }
+    }
+
+    // Indexed collects keep results in event order regardless of which thread ran them
+    let event_results: Vec<ValidationResult> = if parallel {
+        events.par_iter().map(validate_event).collect()
+    } else {
+        events.iter().map(validate_event).collect()
+    };
+
+    for (idx, event_result) in event_results.into_iter().enumerate() {
         if !event_result.is_valid {
             for error in event_result.errors {
                 result.add_error(format!("Event {}: {}", idx, error));
@@ -158,3 +221,88 @@ pub fn validate_batch(events: &[Value]) -> ValidationResult {
     result
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parallel_batch_errors_keep_event_order() {
+        let events: Vec<Value> = (0..PARALLEL_THRESHOLD * 4)
+            .map(|i| {
+                if i % 3 == 0 {
+                    serde_json::json!({"timestamp": i, "data": {}})
+                } else {
+                    serde_json::json!({"timestamp": i, "source": "s", "data": {}})
+                }
+            })
+            .collect();
+
+        let expected: Vec<String> = (0..events.len())
+            .filter(|i| i % 3 == 0)
+            .map(|i| format!("Event {}: Event must have a 'source' field", i))
+            .collect();
+
+        for _ in 0..5 {
+            let result = validate_batch(&events, &BatchLimits::default());
+            assert_eq!(result.errors, expected);
+        }
+    }
+
+    fn batch(len: usize) -> Vec<Value> {
+        (0..len)
+            .map(|i| serde_json::json!({"timestamp": i, "source": "s", "data": {}}))
+            .collect()
+    }
+
+    #[test]
+    fn test_max_events_is_a_hard_limit() {
+        let limits = BatchLimits {
+            max_events: Some(3),
+            ..Default::default()
+        };
+
+        assert!(validate_batch(&batch(3), &limits).is_valid);
+        let result = validate_batch(&batch(4), &limits);
+        assert!(!result.is_valid);
+        assert_eq!(result.errors, ["Batch size 4 exceeds limit of 3 events"]);
+    }
+
+    #[test]
+    fn test_recommended_max_only_warns() {
+        let limits = BatchLimits {
+            recommended_max: 2,
+            ..Default::default()
+        };
+
+        assert!(validate_batch(&batch(2), &limits).warnings.is_empty());
+        let result = validate_batch(&batch(3), &limits);
+        assert!(result.is_valid);
+        assert_eq!(
+            result.warnings,
+            ["Large batch size: 3 events (recommended: < 2)"]
+        );
+    }
+
+    #[test]
+    fn test_max_total_bytes_counts_serialized_events() {
+        let events = batch(2);
+        let size: usize = events.iter().map(|e| e.to_string().len()).sum();
+
+        let limits = BatchLimits {
+            max_total_bytes: Some(size),
+            ..Default::default()
+        };
+        assert!(validate_batch(&events, &limits).is_valid);
+
+        let limits = BatchLimits {
+            max_total_bytes: Some(size - 1),
+            ..Default::default()
+        };
+        let result = validate_batch(&events, &limits);
+        assert!(!result.is_valid);
+        assert_eq!(
+            result.errors,
+            [format!("Batch payload of {} bytes exceeds limit of {} bytes", size, size - 1)]
+        );
+    }
+}
@@ -13,6 +13,45 @@ pub struct Workspace {
     pub api_key: String,
     pub ingest_url: String,
     pub description: Option<String>,
+    /// Pipeline used when a command doesn't specify one
+    #[serde(default)]
+    pub default_pipeline: Option<String>,
+    /// Headers sent with every request to this workspace
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub extra_headers: HashMap<String, String>,
+    /// Headers read from environment variables on every request to this workspace
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env_headers: HashMap<String, String>,
+}
+
+impl Workspace {
+    /// Register an existing config (e.g. a project's `.pynthorarc`) under `name`
+    pub fn from_config(config: &Config, name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            api_key: config.api_key.clone(),
+            ingest_url: config.ingest_url.clone(),
+            description: None,
+            default_pipeline: config.default_pipeline.clone(),
+            extra_headers: config.extra_headers.clone(),
+            env_headers: config.env_headers.clone(),
+        }
+    }
+
+    /// Client config for this workspace
+    pub fn to_config(&self) -> Config {
+        let mut config = Config {
+            api_key: self.api_key.clone(),
+            ingest_url: self.ingest_url.clone(),
+            workspace: self.name.clone(),
+            default_pipeline: self.default_pipeline.clone(),
+            extra_headers: self.extra_headers.clone(),
+            env_headers: self.env_headers.clone(),
+            ..Default::default()
+        };
+        config.normalize_endpoints();
+        config
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -91,11 +130,7 @@ impl WorkspaceManager {
         let workspace = self.get_current()
             .ok_or_else(|| anyhow::anyhow!("No workspace selected"))?;
 
-        Ok(Config {
-            api_key: workspace.api_key.clone(),
-            ingest_url: workspace.ingest_url.clone(),
-            workspace: workspace.name.clone(),
-        })
+        Ok(workspace.to_config())
     }
 
     fn workspace_file_path() -> Result<PathBuf> {
@@ -107,3 +142,24 @@ impl WorkspaceManager {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspace_from_config() {
+        let config = Config::builder()
+            .api_key("test_key_12345678")
+            .workspace("project-a")
+            .default_pipeline("etl")
+            .build()
+            .unwrap();
+
+        let workspace = Workspace::from_config(&config, "imported");
+        assert_eq!(workspace.name, "imported");
+        assert_eq!(workspace.api_key, "test_key_12345678");
+        assert_eq!(workspace.ingest_url, config.ingest_url);
+        assert_eq!(workspace.default_pipeline.as_deref(), Some("etl"));
+    }
+}
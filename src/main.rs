@@ -1,9 +1,23 @@
-use anyhow::Result;
-use clap::{Parser, Subcommand};
-use pynthora_terminal::commands::{init, pipeline, stream};
-use pynthora_terminal::core::config::Config;
+use clap::{ArgMatches, Args, ColorChoice, CommandFactory, FromArgMatches, Parser, Subcommand};
+use colored::Colorize;
+use pynthora_terminal::commands::{
+    bench, config, events, init, ping, pipeline, repl, status, stream, usage, workspace,
+    OutputFormat,
+};
+use pynthora_terminal::core::config::{Config, Overrides};
+use pynthora_terminal::core::error::{Error, EXIT_CONFIG, EXIT_FAILURE};
+use pynthora_terminal::core::history;
+use pynthora_terminal::core::input::InputFormat;
+use pynthora_terminal::core::telemetry::TelemetryEvent;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::SdkTracerProvider;
 use std::process;
-use tracing::{error, info};
+use std::time::{Duration, Instant};
+use tracing::{error, warn};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
 
 #[derive(Parser)]
 #[command(name = "pynthora-terminal")]
@@ -16,6 +30,32 @@ struct Cli {
     /// Path to custom .pynthorarc file
     #[arg(short, long)]
     config: Option<String>,
+
+    /// Config profile to load from a profiles-style .pynthorarc (or set PYNTHORA_PROFILE)
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
+    /// Route requests to this gateway region (`X-Region`), overriding the config's
+    /// `region` (or set PYNTHORA_REGION)
+    #[arg(long, global = true)]
+    region: Option<String>,
+
+    /// Load PYNTHORA_* variables from a dotenv file (real env vars take precedence)
+    #[arg(long, global = true)]
+    env_file: Option<String>,
+
+    /// Export traces to this OTLP/HTTP collector (or set OTEL_EXPORTER_OTLP_ENDPOINT)
+    #[arg(long)]
+    otlp_endpoint: Option<String>,
+
+    /// When to use colors: auto (terminals only, honoring NO_COLOR), always or never
+    #[arg(long, value_enum, global = true, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+
+    /// Append a record of this run (command, duration, counts, outcome) to
+    /// ~/.pynthora/history.ndjson; `record_history: true` in the config does it always
+    #[arg(long, global = true)]
+    record_history: bool,
 }
 
 #[derive(Subcommand)]
@@ -32,20 +72,40 @@ enum Commands {
         subcommand: PipelineCommands,
     },
     /// Stream data to ingestion gateway
-    Stream {
-        /// Input file path
-        #[arg(short, long)]
-        file: String,
-        /// Pipeline ID to use
-        #[arg(short, long)]
-        pipeline: Option<String>,
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+    Stream(Box<StreamCommand>),
+    /// Load-test the gateway with synthetic events at a target rate
+    Bench(bench::BenchArgs),
+    /// Inspect and validate local event files
+    Events {
+        #[command(subcommand)]
+        subcommand: EventCommands,
     },
+    /// Inspect configuration
+    Config {
+        #[command(subcommand)]
+        subcommand: ConfigCommands,
+    },
+    /// Quick connectivity check: prints OK/FAIL with round-trip latency
+    Ping,
     /// Check ingestion status and health
     Status {
         /// Show detailed metrics
         #[arg(short, long)]
         verbose: bool,
+        /// Number of recent samples shown in the RPS/latency sparklines while monitoring
+        #[arg(long, default_value_t = 20)]
+        history: usize,
     },
+    /// Show events used against the workspace quota for the current period
+    Usage,
+    /// Manage named workspaces (~/.pynthora/workspaces.toml)
+    Workspace {
+        #[command(subcommand)]
+        subcommand: WorkspaceCommands,
+    },
+    /// Interactive prompt for status and pipeline commands, reusing one connection
+    Repl,
     /// Manage API keys
     Keys {
         #[command(subcommand)]
@@ -57,8 +117,40 @@ enum Commands {
 enum PipelineCommands {
     /// Push pipeline definition to server
     Push {
-        /// Pipeline definition file (YAML or JSON)
+        /// Pipeline definition file (YAML or JSON), or `-` for stdin
+        file: String,
+        /// Input format (auto uses the extension, or sniffs content when there is none)
+        #[arg(long, value_enum, default_value = "auto")]
+        format: pipeline::PipelineFormat,
+        /// Treat validation warnings as errors
+        #[arg(long)]
+        strict: bool,
+        /// Send YAML definitions as-is (`application/yaml`), falling back to JSON on 415
+        #[arg(long)]
+        send_yaml: bool,
+        /// Skip client-side validation and let the server decide
+        #[arg(long, conflicts_with = "strict")]
+        no_validate: bool,
+        /// Don't refuse to push to gateways older than `min_server_version`
+        #[arg(long)]
+        skip_version_check: bool,
+        /// Dump the request to stderr, with secret headers redacted
+        #[arg(long)]
+        print_request: bool,
+    },
+    /// Validate a pipeline definition locally without pushing it
+    Validate {
+        /// Pipeline definition file (YAML or JSON), or `-` for stdin
         file: String,
+        /// Input format (auto uses the extension, or sniffs content when there is none)
+        #[arg(long, value_enum, default_value = "auto")]
+        format: pipeline::PipelineFormat,
+        /// Treat validation warnings as errors
+        #[arg(long)]
+        strict: bool,
+        /// Report results as human-readable text or a JSON `ValidationResult`
+        #[arg(long, value_enum, default_value = "human")]
+        output: OutputFormat,
     },
     /// List all pipelines
     List,
@@ -67,6 +159,132 @@ enum PipelineCommands {
         /// Pipeline ID
         id: String,
     },
+    /// Compare a deployed pipeline with a local definition
+    Diff {
+        /// Pipeline ID
+        id: String,
+        /// Local pipeline definition file (YAML or JSON)
+        file: String,
+    },
+    /// Show recent runs of a pipeline
+    Runs {
+        /// Pipeline ID
+        id: String,
+        /// Maximum number of runs to show
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Export all pipelines to a directory
+    Export {
+        /// Output directory
+        #[arg(short, long)]
+        dir: String,
+        /// Output format
+        #[arg(long, value_enum, default_value = "json")]
+        format: pipeline::ExportFormat,
+    },
+    /// Push every pipeline definition found in a directory
+    Import {
+        /// Directory of .json/.yaml/.ndjson definitions
+        #[arg(short, long)]
+        dir: String,
+    },
+}
+
+/// Boxed in `Commands` since the stream flags dwarf every other variant
+#[derive(Args)]
+struct StreamCommand {
+    #[command(subcommand)]
+    subcommand: Option<StreamCommands>,
+    #[command(flatten)]
+    args: Option<stream::StreamArgs>,
+}
+
+#[derive(Subcommand)]
+enum StreamCommands {
+    /// Re-send the events in a --dead-letter file, skipping ones the server already has
+    Replay(stream::ReplayArgs),
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Load and validate the config (from --config, env vars or .pynthorarc) without using it
+    Validate,
+    /// Print the effective config with the API key masked and where each value came from
+    Show,
+}
+
+#[derive(Subcommand)]
+enum EventCommands {
+    /// Validate an NDJSON events file without streaming it
+    Validate {
+        /// Input file path
+        #[arg(short, long)]
+        file: String,
+        /// Input format (ndjson, json, csv); detected when omitted
+        #[arg(long)]
+        input_format: Option<InputFormat>,
+        /// Treat validation warnings as errors
+        #[arg(long)]
+        strict: bool,
+        /// Report results as human-readable text or a JSON `ValidationResult`
+        #[arg(long, value_enum, default_value = "human")]
+        output: OutputFormat,
+    },
+    /// Summarize an events file (lines, parse errors, event types) without sending it
+    Inspect {
+        /// Input file path
+        #[arg(short, long)]
+        file: String,
+        /// Input format (ndjson, json, csv); detected when omitted
+        #[arg(long)]
+        input_format: Option<InputFormat>,
+    },
+    /// Split an events file into N shards for parallel streaming
+    Split {
+        /// Input file path
+        #[arg(short, long)]
+        file: String,
+        /// Input format (ndjson, json, csv); detected when omitted
+        #[arg(long)]
+        input_format: Option<InputFormat>,
+        /// Number of shards to write
+        #[arg(long)]
+        parts: usize,
+        /// Directory to write shards into
+        #[arg(long)]
+        out_dir: String,
+    },
+    /// Convert an events file between CSV, a JSON array and NDJSON
+    Convert {
+        /// Input file path
+        #[arg(long = "in")]
+        input: String,
+        /// Input format (ndjson, json, csv); detected when omitted
+        #[arg(long)]
+        from: Option<InputFormat>,
+        /// Output format
+        #[arg(long, value_enum)]
+        to: events::ConvertFormat,
+        /// Output file path, or `-` for stdout
+        #[arg(long)]
+        out: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum WorkspaceCommands {
+    /// Register an existing .pynthorarc as a named workspace
+    Import {
+        /// Config file to import
+        #[arg(long)]
+        config: String,
+        /// Workspace name (defaults to the config's `workspace`)
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// Health-check every registered workspace and report which are reachable
+    Check,
 }
 
 #[derive(Subcommand)]
@@ -83,134 +301,277 @@ enum KeyCommands {
 
 #[tokio::main]
 async fn main() {
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let command_name = subcommand_path(&matches);
+    apply_color_choice(cli.color);
+
+    // Loaded before tracing so RUST_LOG and OTEL_EXPORTER_OTLP_ENDPOINT can come from it
+    if let Some(env_file) = &cli.env_file {
+        if let Err(e) = dotenvy::from_path(env_file) {
+            eprintln!("{} Failed to load env file {}: {}", "✗".red(), env_file, e);
+            process::exit(EXIT_CONFIG);
+        }
+    }
+
     // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-        .with_target(false)
-        .init();
+    let otlp_endpoint = cli
+        .otlp_endpoint
+        .clone()
+        .or_else(|| std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok());
+    let tracer_provider = init_tracing(otlp_endpoint.as_deref(), cli.color);
 
-    let cli = Cli::parse();
+    // Flags win over the environment, including variables loaded from --env-file
+    let env = Overrides::from_env();
+    let overrides = Overrides {
+        profile: cli.profile.clone().or(env.profile),
+        region: cli.region.clone().or(env.region),
+    };
 
-    // Load config if needed (skip for init command)
-    if !matches!(cli.command, Commands::Init { .. }) {
-        if let Err(e) = Config::load(cli.config.as_deref()) {
-            error!("Failed to load configuration: {}", e);
-            error!("Run 'pynthora-terminal init' to create a configuration file");
-            process::exit(1);
+    // Load config if needed (skip for commands that work offline)
+    if !matches!(
+        cli.command,
+        Commands::Init { .. }
+            | Commands::Config { .. }
+            | Commands::Events { .. }
+            | Commands::Workspace { .. }
+            | Commands::Pipeline {
+                subcommand: PipelineCommands::Validate { .. }
+            }
+    ) {
+        match Config::load_with(cli.config.as_deref(), &overrides) {
+            Ok(config) => {
+                if let Some(warning) = config.region_warning() {
+                    eprintln!("{} {}", "⚠".yellow(), warning);
+                }
+            }
+            Err(e) => {
+                error!("Failed to load configuration: {}", e);
+                error!("Run 'pynthora-terminal init' to create a configuration file");
+                process::exit(EXIT_CONFIG);
+            }
         }
     }
 
+    let config_path = cli.config.clone();
+    let record_history = cli.record_history;
+    let started = Instant::now();
     let result = match cli.command {
         Commands::Init { force } => init::run(force).await,
         Commands::Pipeline { subcommand } => match subcommand {
-            PipelineCommands::Push { file } => pipeline::push(&file).await,
+            PipelineCommands::Push {
+                file,
+                format,
+                strict,
+                send_yaml,
+                no_validate,
+                skip_version_check,
+                print_request,
+            } => {
+                pipeline::push(
+                    &file,
+                    format,
+                    strict,
+                    send_yaml,
+                    no_validate,
+                    skip_version_check,
+                    print_request,
+                )
+                .await
+            }
+            PipelineCommands::Validate {
+                file,
+                format,
+                strict,
+                output,
+            } => pipeline::validate(&file, format, strict, output).await,
             PipelineCommands::List => pipeline::list().await,
             PipelineCommands::Show { id } => pipeline::show(&id).await,
+            PipelineCommands::Diff { id, file } => pipeline::diff(&id, &file).await,
+            PipelineCommands::Runs { id, limit } => pipeline::runs(&id, limit).await,
+            PipelineCommands::Export { dir, format } => pipeline::export(&dir, format).await,
+            PipelineCommands::Import { dir } => pipeline::import(&dir).await,
+        },
+        Commands::Stream(command) => match command.subcommand {
+            Some(StreamCommands::Replay(args)) => stream::replay(&args).await,
+            None => {
+                let args = command.args.expect("clap requires --file without a subcommand");
+                stream::run(&args).await
+            }
+        },
+        Commands::Bench(args) => bench::run(&args).await,
+        Commands::Events { subcommand } => match subcommand {
+            EventCommands::Validate {
+                file,
+                input_format,
+                strict,
+                output,
+            } => events::validate(&file, input_format, strict, output).await,
+            EventCommands::Inspect { file, input_format } => {
+                events::inspect(&file, input_format).await
+            }
+            EventCommands::Split {
+                file,
+                input_format,
+                parts,
+                out_dir,
+            } => events::split(&file, input_format, parts, &out_dir).await,
+            EventCommands::Convert {
+                input,
+                from,
+                to,
+                out,
+            } => events::convert(&input, from, to, &out).await,
+        },
+        Commands::Config { subcommand } => match subcommand {
+            ConfigCommands::Validate => config::validate(cli.config.as_deref(), &overrides).await,
+            ConfigCommands::Show => config::show(cli.config.as_deref(), &overrides).await,
         },
-        Commands::Stream { file, pipeline } => stream::run(&file, pipeline.as_deref()).await,
-        Commands::Status { verbose } => status::run(verbose).await,
+        Commands::Ping => ping::run().await,
+        Commands::Status { verbose, history } => status::run(verbose, history).await,
+        Commands::Usage => usage::run().await,
+        Commands::Workspace { subcommand } => match subcommand {
+            WorkspaceCommands::Import { config, name } => {
+                workspace::import(&config, name.as_deref()).await
+            }
+            WorkspaceCommands::Check => workspace::check().await,
+        },
+        Commands::Repl => repl::run().await,
         Commands::Keys { subcommand } => match subcommand {
             KeyCommands::Rotate { force } => keys::rotate(force).await,
             KeyCommands::Show => keys::show().await,
         },
     };
 
-    if let Err(e) = result {
-        error!("Error: {}", e);
-        process::exit(1);
+    let code = match &result {
+        Ok(()) => 0,
+        Err(e) => {
+            error!("Error: {}", e);
+            exit_code(e)
+        }
+    };
+
+    let record_history = record_history
+        || Config::load_with(config_path.as_deref(), &overrides).is_ok_and(|config| config.record_history);
+    if record_history {
+        let error = result.as_ref().err().map(|e| e.to_string());
+        record_run(&command_name, started.elapsed(), code, error);
     }
-}
 
-// Status command with real-time health monitoring (v0.2.0)
-mod status {
-    use anyhow::{Context, Result};
-    use colored::*;
-    use indicatif::{ProgressBar, ProgressStyle};
-    use pynthora_terminal::core::config::Config;
-    use pynthora_terminal::sdk::client::Client;
-    use std::time::Duration;
-    use tokio::time::sleep;
+    // process::exit skips destructors, so flush pending spans explicitly
+    if let Some(provider) = tracer_provider {
+        if let Err(e) = provider.shutdown() {
+            eprintln!("Failed to flush traces: {}", e);
+        }
+    }
 
-    pub async fn run(verbose: bool) -> Result<()> {
-        let config = Config::load(None)?;
-        let client = Client::new(config);
+    if code != 0 {
+        process::exit(code);
+    }
+}
+
+/// Force colors on or off for `colored` output and `indicatif` bars; `auto` leaves
+/// each to its own TTY and `NO_COLOR` detection
+fn apply_color_choice(choice: ColorChoice) {
+    let enabled = match choice {
+        ColorChoice::Auto => return,
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+    };
+    colored::control::set_override(enabled);
+    console::set_colors_enabled(enabled);
+    console::set_colors_enabled_stderr(enabled);
+}
 
-        println!("{} Checking pynthora terminal health...", "ℹ".blue());
+/// Install the log formatter, plus an OTLP span exporter when an endpoint is given
+fn init_tracing(otlp_endpoint: Option<&str>, color: ColorChoice) -> Option<SdkTracerProvider> {
+    // Logs go to stderr so `--output json` leaves stdout machine-readable
+    let fmt_layer = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_target(false);
+    let fmt_layer = match color {
+        ColorChoice::Auto => fmt_layer,
+        ColorChoice::Always => fmt_layer.with_ansi(true),
+        ColorChoice::Never => fmt_layer.with_ansi(false),
+    }
+    .with_filter(EnvFilter::from_default_env());
 
-        let health = client.health_check().await
-            .context("Failed to check health status")?;
+    let provider = otlp_endpoint.and_then(|endpoint| {
+        let exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_http()
+            .with_endpoint(endpoint)
+            .build();
 
-        println!("\n{} Health Status", "=".cyan().bold());
-        println!("  Status: {}", 
-            if health.status == "healthy" { 
-                "✓ Healthy".green() 
-            } else { 
-                format!("✗ {}", health.status).red() 
+        match exporter {
+            Ok(exporter) => Some(
+                SdkTracerProvider::builder()
+                    .with_batch_exporter(exporter)
+                    .with_resource(
+                        opentelemetry_sdk::Resource::builder()
+                            .with_service_name("pynthora-terminal")
+                            .build(),
+                    )
+                    .build(),
+            ),
+            Err(e) => {
+                eprintln!("Failed to initialize OTLP exporter for {}: {}", endpoint, e);
+                None
             }
-        );
-
-        if let Some(version) = health.version {
-            println!("  Version: {}", version);
         }
+    });
 
-        if let Some(uptime) = health.uptime {
-            let hours = uptime / 3600;
-            let minutes = (uptime % 3600) / 60;
-            println!("  Uptime: {}h {}m", hours, minutes);
-        }
+    // Spans are exported independently of RUST_LOG, which only governs console output
+    let otel_layer = provider.as_ref().map(|provider| {
+        tracing_opentelemetry::layer()
+            .with_tracer(provider.tracer("pynthora-terminal"))
+            .with_filter(EnvFilter::new("pynthora_terminal=info"))
+    });
 
-        if verbose {
-            if let Some(metrics) = health.metrics {
-                println!("\n{} Metrics", "=".cyan().bold());
-                if let Some(total) = metrics.requests_total {
-                    println!("  Total Requests: {}", total);
-                }
-                if let Some(rps) = metrics.requests_per_second {
-                    println!("  Requests/sec: {:.2}", rps);
-                }
-                if let Some(latency) = metrics.latency_ms {
-                    println!("  Avg Latency: {:.2}ms", latency);
-                }
-            }
-        }
+    tracing_subscriber::registry()
+        .with(fmt_layer)
+        .with(otel_layer)
+        .init();
 
-        // Real-time monitoring mode (v0.2.0)
-        if verbose {
-            println!("\n{} Starting real-time monitoring (Ctrl+C to stop)...", "ℹ".blue());
-            
-            let pb = ProgressBar::new_spinner();
-            pb.set_style(
-                ProgressStyle::default_spinner()
-                    .template("{spinner:.green} Monitoring... {msg}")
-                    .unwrap(),
-            );
-
-            loop {
-                match client.health_check().await {
-                    Ok(health) => {
-                        let status_icon = if health.status == "healthy" { "✓" } else { "✗" };
-                        let status_color = if health.status == "healthy" { "green" } else { "red" };
-                        
-                        let mut msg = format!("{} Status: {}", status_icon, health.status);
-                        if let Some(metrics) = &health.metrics {
-                            if let Some(rps) = metrics.requests_per_second {
-                                msg.push_str(&format!(" | RPS: {:.2}", rps));
-                            }
-                        }
-                        
-                        pb.set_message(msg);
-                    }
-                    Err(e) => {
-                        pb.set_message(format!("✗ Error: {}", e));
-                    }
-                }
-                
-                sleep(Duration::from_secs(2)).await;
-            }
-        }
+    provider
+}
 
-        Ok(())
+/// The subcommands invoked, e.g. `pipeline push`
+fn subcommand_path(matches: &ArgMatches) -> String {
+    let mut names = Vec::new();
+    let mut current = matches;
+    while let Some((name, sub)) = current.subcommand() {
+        names.push(name);
+        current = sub;
     }
+    names.join(" ")
+}
+
+/// Append this run to the history file; failing to is only worth a warning
+fn record_run(command: &str, elapsed: Duration, code: i32, error: Option<String>) {
+    let event = TelemetryEvent::new(
+        history::RUN_EVENT_TYPE,
+        serde_json::json!({
+            "command": command,
+            "duration_ms": elapsed.as_millis() as u64,
+            "success": code == 0,
+            "exit_code": code,
+            "error": error,
+            "counts": history::take_counts(),
+        }),
+    )
+    .with_metadata(serde_json::json!({"version": env!("CARGO_PKG_VERSION")}));
+
+    if let Err(e) = history::default_path().and_then(|path| history::append(&path, &event)) {
+        warn!("Failed to record run history: {}", e);
+    }
+}
+
+/// Map an error to its category's exit code, looking through any added context
+fn exit_code(err: &anyhow::Error) -> i32 {
+    err.chain()
+        .find_map(|cause| cause.downcast_ref::<Error>())
+        .map(Error::exit_code)
+        .unwrap_or(EXIT_FAILURE)
 }
 
 mod keys {
@@ -1,35 +1,297 @@
-use anyhow::{Context, Result};
-use pynthora_terminal::core::config::Config;
+use pynthora_terminal::core::config::{AuthScheme, Config};
+use pynthora_terminal::core::error::{Error, Result};
+use pynthora_terminal::core::retry::{retry_with_backoff_if, BackoffStrategy, RetryConfig};
+use pynthora_terminal::core::tls::TlsSettings;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use futures_util::stream::{self, Stream, StreamExt};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use reqwest::Client as HttpClient;
+use reqwest::{Body, Request, RequestBuilder, Response};
 use serde_json::Value;
-use std::sync::Arc;
-use std::time::Duration;
-use tracing::{debug, error};
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
+use tracing::{debug, enabled, error, instrument, warn, Level};
+
+const DEFAULT_HEALTH_TIMEOUT_SECS: u64 = 5;
+/// Health checks retry failed connections and lookups this many times in total
+const HEALTH_CHECK_ATTEMPTS: u32 = 3;
+const DEFAULT_POOL_MAX_IDLE_PER_HOST: usize = 10;
+const DEFAULT_POOL_IDLE_TIMEOUT_SECS: u64 = 90;
+const DEFAULT_USER_AGENT: &str = concat!("pynthora-terminal/", env!("CARGO_PKG_VERSION"));
+
+/// Wire encoding for batch bodies; events stay `serde_json::Value` either way
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Encoding {
+    #[default]
+    Json,
+    /// MessagePack, cheaper to encode and smaller on the wire for high-throughput ingest
+    Msgpack,
+}
+
+impl Encoding {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Encoding::Json => "application/json",
+            Encoding::Msgpack => "application/msgpack",
+        }
+    }
+
+    /// Serialize a batch of events in this encoding
+    pub fn encode(&self, events: &[Value]) -> Result<Vec<u8>> {
+        match self {
+            Encoding::Json => serde_json::to_vec(events)
+                .map_err(|e| Error::Decode(format!("batch as JSON: {}", e))),
+            Encoding::Msgpack => rmp_serde::to_vec(events)
+                .map_err(|e| Error::Decode(format!("batch as MessagePack: {}", e))),
+        }
+    }
+}
+
+impl std::str::FromStr for Encoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Encoding::Json),
+            "msgpack" => Ok(Encoding::Msgpack),
+            other => Err(format!("unknown encoding '{}' (json, msgpack)", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for Encoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Encoding::Json => "json",
+            Encoding::Msgpack => "msgpack",
+        })
+    }
+}
+
+/// Headers whose values must never reach the logs
+const SENSITIVE_HEADERS: &[&str] = &["authorization", "x-api-key"];
 
 pub struct Client {
     config: Arc<Config>,
     http_client: HttpClient,
+    /// Index into `config.endpoints()` of the last endpoint that answered
+    active_endpoint: AtomicUsize,
+    /// Gzip level for batch bodies, when compression is enabled
+    compression: Option<Compression>,
+    /// Serialization used for batch bodies
+    encoding: Encoding,
+    /// Batch body bytes before compression
+    raw_bytes: AtomicU64,
+    /// Batch body bytes actually sent
+    sent_bytes: AtomicU64,
+    /// Whether `check_server_version` enforces `min_server_version`
+    version_check: bool,
+    /// Gateway version from `/health`, fetched on first use
+    server_version: OnceCell<Option<semver::Version>>,
+    health_cache: Option<HealthCache>,
+    /// Sent as `X-Correlation-Id` so the gateway's logs can be matched to a run
+    correlation_id: Option<String>,
+    /// Headers whose values come from environment variables, read per request
+    env_headers: Vec<(String, String)>,
+    /// Dump request bodies to stderr: the first one sent, and any that fail
+    print_requests: bool,
+    request_printed: AtomicBool,
+}
+
+/// Last `/health` answer, reused by `health_check` until it is `ttl` old
+struct HealthCache {
+    ttl: Duration,
+    last: Mutex<Option<(Instant, HealthStatus)>>,
+}
+
+impl HealthCache {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            last: Mutex::new(None),
+        }
+    }
+
+    fn get(&self) -> Option<HealthStatus> {
+        match &*self.last.lock().unwrap() {
+            Some((fetched, health)) if fetched.elapsed() < self.ttl => Some(health.clone()),
+            _ => None,
+        }
+    }
+
+    fn put(&self, health: &HealthStatus) {
+        *self.last.lock().unwrap() = Some((Instant::now(), health.clone()));
+    }
 }
 
 impl Client {
-    pub fn new(config: Config) -> Self {
+    /// Create a client, failing with `Error::Config` if the configured TLS files
+    /// can't be loaded
+    pub fn new(config: Config) -> Result<Self> {
         // Create HTTP client with optimized settings
-        let http_client = HttpClient::builder()
+        let builder = HttpClient::builder()
             .timeout(Duration::from_secs(30))
             .connect_timeout(Duration::from_secs(10))
-            .pool_max_idle_per_host(10)
-            .pool_idle_timeout(Duration::from_secs(90))
+            .user_agent(
+                config
+                    .user_agent
+                    .as_deref()
+                    .unwrap_or(DEFAULT_USER_AGENT),
+            )
+            .pool_max_idle_per_host(
+                config
+                    .pool_max_idle_per_host
+                    .unwrap_or(DEFAULT_POOL_MAX_IDLE_PER_HOST),
+            )
+            .pool_idle_timeout(Duration::from_secs(
+                config
+                    .pool_idle_timeout_secs
+                    .unwrap_or(DEFAULT_POOL_IDLE_TIMEOUT_SECS),
+            ));
+
+        let http_client = TlsSettings::from_config(&config)
+            .and_then(|tls| tls.apply_to_http(builder))
+            .map_err(|e| Error::Config(format!("{:#}", e)))?
             .build()
-            .expect("Failed to create HTTP client");
+            .map_err(Error::Network)?;
 
-        Self {
+        let mut env_headers: Vec<(String, String)> = config
+            .env_headers
+            .iter()
+            .map(|(name, var)| (name.clone(), var.clone()))
+            .collect();
+        env_headers.sort();
+
+        Ok(Self {
             config: Arc::new(config),
             http_client,
+            active_endpoint: AtomicUsize::new(0),
+            compression: None,
+            encoding: Encoding::default(),
+            raw_bytes: AtomicU64::new(0),
+            sent_bytes: AtomicU64::new(0),
+            version_check: true,
+            server_version: OnceCell::new(),
+            health_cache: None,
+            correlation_id: None,
+            env_headers,
+            print_requests: false,
+            request_printed: AtomicBool::new(false),
+        })
+    }
+
+    /// Gzip batch bodies (`Content-Encoding: gzip`) at the given level
+    pub fn with_compression(mut self, level: Compression) -> Self {
+        self.compression = Some(level);
+        self
+    }
+
+    /// Serialize batch bodies with `encoding` instead of JSON
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Let `health_check` answer from the last successful check for up to `ttl`,
+    /// to spare the gateway when health is polled often
+    pub fn with_health_cache(mut self, ttl: Duration) -> Self {
+        self.health_cache = Some(HealthCache::new(ttl));
+        self
+    }
+
+    /// Write the method, URL, headers (credentials redacted) and body of the first
+    /// request with a body to stderr, and of every request that fails, for debugging
+    /// what the gateway rejected. Streamed bodies (`batch_ingest_stream`) aren't shown
+    pub fn with_request_printing(mut self) -> Self {
+        self.print_requests = true;
+        self
+    }
+
+    /// Tag every request with `X-Correlation-Id: id`
+    pub fn with_correlation_id(mut self, id: impl Into<String>) -> Self {
+        self.correlation_id = Some(id.into());
+        self
+    }
+
+    /// Send header `name` with the current value of environment variable `var` on
+    /// every request, in addition to the config's `env_headers`
+    pub fn with_env_header(mut self, name: impl Into<String>, var: impl Into<String>) -> Self {
+        self.env_headers.push((name.into(), var.into()));
+        self
+    }
+
+    /// Don't enforce `min_server_version` (for `--skip-version-check`)
+    pub fn without_version_check(mut self) -> Self {
+        self.version_check = false;
+        self
+    }
+
+    /// Fail with `Error::UnsupportedServer` if the gateway is older than the configured
+    /// `min_server_version`.
+    ///
+    /// The version comes from `/health`, fetched once per client. A gateway that can't be
+    /// reached or doesn't report a parseable version is let through with a warning, so
+    /// the real request can fail with a more useful error.
+    pub async fn check_server_version(&self, feature: &str) -> Result<()> {
+        let Some(required) = self.config.min_server_version.as_deref() else {
+            return Ok(());
+        };
+        if !self.version_check {
+            return Ok(());
         }
+        let required_version = semver::Version::parse(required)
+            .map_err(|e| Error::Config(format!("min_server_version '{}': {}", required, e)))?;
+
+        let server_version = self
+            .server_version
+            .get_or_init(|| async {
+                match self.health_check(false).await {
+                    Ok(health) => match health.version.as_deref().map(parse_server_version) {
+                        Some(Some(version)) => Some(version),
+                        Some(None) | None => {
+                            warn!("Gateway did not report a semver version; skipping version check");
+                            None
+                        }
+                    },
+                    Err(e) => {
+                        warn!("Could not fetch gateway version ({}); skipping version check", e);
+                        None
+                    }
+                }
+            })
+            .await;
+
+        match server_version {
+            Some(version) if *version < required_version => Err(Error::UnsupportedServer {
+                version: version.to_string(),
+                required: required.to_string(),
+                feature: feature.to_string(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether batch bodies are gzip-compressed
+    pub fn compression_enabled(&self) -> bool {
+        self.compression.is_some()
     }
 
+    /// Total batch body bytes so far, as `(before compression, sent)`
+    pub fn batch_bytes(&self) -> (u64, u64) {
+        (
+            self.raw_bytes.load(Ordering::Relaxed),
+            self.sent_bytes.load(Ordering::Relaxed),
+        )
+    }
+
+    /// The ingest endpoint currently in use (changes after a failover)
     pub fn base_url(&self) -> &str {
-        &self.config.ingest_url
+        let endpoints = self.config.endpoints();
+        endpoints[self.active_endpoint.load(Ordering::Relaxed) % endpoints.len()]
     }
 
     pub fn api_key(&self) -> &str {
@@ -40,132 +302,624 @@ impl Client {
         &self.config.workspace
     }
 
-    /// Stream a single event
+    /// Attach the API key using the configured `auth_scheme`, env-sourced headers, and
+    /// the correlation id if set
+    fn authorized(&self, request: RequestBuilder) -> RequestBuilder {
+        let scheme = self.auth_scheme();
+        let mut request =
+            request.header(scheme.header_name(), scheme.header_value(self.api_key()));
+        if let Some(region) = &self.config.region {
+            request = request.header("X-Region", region);
+        }
+        // Read on every request so a rotated token is picked up; `send` rejects unset ones
+        for (name, var) in &self.env_headers {
+            if let Ok(value) = std::env::var(var) {
+                request = request.header(name, value);
+            }
+        }
+        match &self.correlation_id {
+            Some(id) => request.header("X-Correlation-Id", id),
+            None => request,
+        }
+    }
+
+    /// Headers to redact in debug logs besides `SENSITIVE_HEADERS`
+    fn secret_headers(&self) -> Vec<&str> {
+        std::iter::once(self.auth_scheme().header_name())
+            .chain(self.env_headers.iter().map(|(name, _)| name.as_str()))
+            .collect()
+    }
+
+    /// Fail with `Error::Config` when a variable backing an env-sourced header is unset
+    pub fn check_env_headers(&self) -> Result<()> {
+        for (name, var) in &self.env_headers {
+            if std::env::var_os(var).is_none() {
+                return Err(Error::Config(format!(
+                    "header {} is read from ${}, which is not set",
+                    name, var
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn auth_scheme(&self) -> &AuthScheme {
+        self.config.auth_scheme.as_ref().unwrap_or(&AuthScheme::Bearer)
+    }
+
+    /// Resolve a per-call workspace override, validated like a configured workspace
+    fn workspace_or_default<'a>(&'a self, workspace: Option<&'a str>) -> Result<&'a str> {
+        match workspace {
+            Some(ws) if ws.trim().is_empty() => Err(Error::Validation(
+                "workspace override must be a non-empty string".to_string(),
+            )),
+            Some(ws) => Ok(ws),
+            None => Ok(self.workspace()),
+        }
+    }
+
+    /// The explicit pipeline if given, otherwise the configured default
+    fn pipeline_or_default<'a>(&'a self, pipeline: Option<&'a str>) -> Option<&'a str> {
+        pipeline.or(self.config.default_pipeline.as_deref())
+    }
+
+    /// Stream a single event, optionally to a workspace other than the configured one
     pub async fn stream_event(
         &self,
         event: &Value,
         pipeline: Option<&str>,
+        workspace: Option<&str>,
     ) -> Result<()> {
-        let url = format!("{}/api/v1/ingest", self.base_url());
-        
-        let mut request = self
-            .http_client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.api_key()))
-            .header("X-Workspace", self.workspace())
-            .header("Content-Type", "application/json")
-            .json(event);
-
-        if let Some(pipeline_id) = pipeline {
-            request = request.header("X-Pipeline-Id", pipeline_id);
-        }
+        let pipeline = self.pipeline_or_default(pipeline);
+        let workspace = self.workspace_or_default(workspace)?;
+        let request = |url: &str| {
+            let mut request = self
+                .authorized(self.http_client.post(url))
+                .header("X-Workspace", workspace)
+                .header("Content-Type", "application/json")
+                .json(event);
 
-        let response = request.send().await.context("Failed to send request")?;
+            if let Some(pipeline_id) = pipeline {
+                request = request.header("X-Pipeline-Id", pipeline_id);
+            }
+            request
+        };
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            error!("Request failed with status {}: {}", status, error_text);
-            anyhow::bail!("Request failed: {}", status);
+        if let Err(err) = self.execute("/ingest", request).await {
+            error!("Request failed: {}", err);
+            return Err(err);
         }
 
         debug!("Event streamed successfully");
         Ok(())
     }
 
-    /// Stream a batch of events (v0.2.0 feature)
+    /// Stream a batch of events (v0.2.0 feature), optionally to a workspace override.
+    ///
+    /// Gateways that process batches asynchronously answer with a batch id (in
+    /// `X-Batch-Id` or a `batch_id` body field), returned for `get_batch_status`.
+    #[instrument(skip_all, fields(events = events.len(), pipeline = pipeline.unwrap_or_default()))]
     pub async fn stream_batch(
         &self,
         events: &[Value],
         pipeline: Option<&str>,
-    ) -> Result<()> {
+        workspace: Option<&str>,
+    ) -> Result<Option<String>> {
+        let pipeline = self.pipeline_or_default(pipeline);
+        let workspace = self.workspace_or_default(workspace)?;
         if events.is_empty() {
-            return Ok(());
+            return Ok(None);
         }
 
-        let url = format!("{}/api/v1/ingest/batch", self.base_url());
-        
+        let body = self.encode_batch(events)?;
+        let request = |url: &str| {
+            let mut request = self
+                .authorized(self.http_client.post(url))
+                .header("X-Workspace", workspace)
+                .header("Content-Type", self.encoding.content_type())
+                .body(body.clone());
+
+            if self.compression.is_some() {
+                request = request.header("Content-Encoding", "gzip");
+            }
+            if let Some(pipeline_id) = pipeline {
+                request = request.header("X-Pipeline-Id", pipeline_id);
+            }
+            request
+        };
+
+        let response = match self.execute("/ingest/batch", request).await {
+            Ok(response) => response,
+            Err(err) => {
+                error!("Batch request failed: {}", err);
+                return Err(err);
+            }
+        };
+
+        debug!("Batch of {} events streamed successfully", events.len());
+        Ok(batch_id(response).await)
+    }
+
+    /// Fetch the processing state of a batch accepted by `stream_batch`
+    pub async fn get_batch_status(&self, batch_id: &str) -> Result<BatchStatus> {
+        let path = format!("/ingest/batch/{}", batch_id);
+        let request = |url: &str| {
+            self.authorized(self.http_client.get(url))
+                .header("X-Workspace", self.workspace())
+        };
+
+        let response = self.execute(&path, request).await?;
+
+        response
+            .json()
+            .await
+            .map_err(|e| Error::Decode(format!("batch status: {}", e)))
+    }
+
+    /// Send an arbitrarily large batch as a single chunked request, serializing events
+    /// into the JSON array body as it is written instead of buffering it.
+    ///
+    /// A streamed body can't be replayed, so this goes to the active endpoint only (no
+    /// failover) and ignores compression and `--encoding`.
+    #[instrument(skip_all, fields(pipeline = pipeline.unwrap_or_default()))]
+    pub async fn batch_ingest_stream<S>(
+        &self,
+        events: S,
+        pipeline: Option<&str>,
+        workspace: Option<&str>,
+    ) -> Result<()>
+    where
+        S: Stream<Item = Value> + Send + 'static,
+    {
+        let pipeline = self.pipeline_or_default(pipeline);
+        let workspace = self.workspace_or_default(workspace)?;
+
+        let url = format!("{}{}/ingest/batch", self.base_url(), self.config.api_prefix());
         let mut request = self
-            .http_client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.api_key()))
-            .header("X-Workspace", self.workspace())
+            .authorized(self.http_client.post(url))
+            .header("X-Workspace", workspace)
             .header("Content-Type", "application/json")
-            .json(events);
+            .body(Body::wrap_stream(json_array_body(events)));
 
         if let Some(pipeline_id) = pipeline {
             request = request.header("X-Pipeline-Id", pipeline_id);
         }
+        for (name, value) in &self.config.extra_headers {
+            request = request.header(name, value);
+        }
 
-        let response = request.send().await.context("Failed to send batch request")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            error!("Batch request failed with status {}: {}", status, error_text);
-            anyhow::bail!("Batch request failed: {}", status);
+        if let Err(err) = self.send(request).await {
+            error!("Streamed batch request failed: {}", err);
+            return Err(err);
         }
 
-        debug!("Batch of {} events streamed successfully", events.len());
+        debug!("Streamed batch sent successfully");
         Ok(())
     }
 
-    /// Get health status
-    pub async fn health_check(&self) -> Result<HealthStatus> {
-        let url = format!("{}/api/v1/health", self.base_url());
-        
-        let response = self
-            .http_client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", self.api_key()))
-            .header("X-Workspace", self.workspace())
-            .send()
-            .await
-            .context("Failed to check health")?;
+    /// Serialize a batch body, gzipping it when compression is enabled
+    fn encode_batch(&self, events: &[Value]) -> Result<Vec<u8>> {
+        let encoded = self.encoding.encode(events)?;
+        let raw_len = encoded.len() as u64;
+        let body = match self.compression {
+            Some(level) => {
+                let mut encoder = GzEncoder::new(Vec::with_capacity(encoded.len() / 4), level);
+                encoder.write_all(&encoded)?;
+                encoder.finish()?
+            }
+            None => encoded,
+        };
 
-        if !response.status().is_success() {
-            anyhow::bail!("Health check failed: {}", response.status());
+        self.raw_bytes.fetch_add(raw_len, Ordering::Relaxed);
+        self.sent_bytes.fetch_add(body.len() as u64, Ordering::Relaxed);
+        Ok(body)
+    }
+
+    /// Timeout applied to health checks, independent of the client-wide timeout
+    pub fn health_timeout(&self) -> Duration {
+        Duration::from_secs(
+            self.config
+                .health_timeout_secs
+                .unwrap_or(DEFAULT_HEALTH_TIMEOUT_SECS),
+        )
+    }
+
+    /// Get health status; with `with_health_cache`, a recent result is returned
+    /// unless `force` is set
+    pub async fn health_check(&self, force: bool) -> Result<HealthStatus> {
+        let cached = self.health_cache.as_ref().filter(|_| !force);
+        if let Some(health) = cached.and_then(HealthCache::get) {
+            return Ok(health);
         }
 
+        // A resolver or connection blip shouldn't report the gateway as down
+        self.fetch_health(HEALTH_CHECK_ATTEMPTS).await
+    }
+
+    /// A single health round-trip, bypassing the cache and never retried, so a
+    /// failure or timeout is reported as soon as it happens
+    pub async fn ping(&self) -> Result<HealthStatus> {
+        self.fetch_health(1).await
+    }
+
+    async fn fetch_health(&self, attempts: u32) -> Result<HealthStatus> {
+        let request = |url: &str| {
+            self.authorized(self.http_client.get(url))
+                .header("X-Workspace", self.workspace())
+                .timeout(self.health_timeout())
+        };
+
+        let retry = RetryConfig {
+            max_attempts: attempts,
+            initial_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(1),
+            strategy: BackoffStrategy::default(),
+        };
+        let response = retry_with_backoff_if(
+            &retry,
+            || self.execute("/health", request),
+            is_transient_network_error,
+        )
+        .await?;
+
         let status: HealthStatus = response
             .json()
             .await
-            .context("Failed to parse health response")?;
+            .map_err(|e| Error::Decode(format!("health response: {}", e)))?;
 
+        if let Some(cache) = &self.health_cache {
+            cache.put(&status);
+        }
         Ok(status)
     }
 
     /// Push pipeline definition
+    #[instrument(skip_all)]
     pub async fn push_pipeline(&self, pipeline: &Value) -> Result<PipelineResponse> {
-        let url = format!("{}/api/v1/pipelines", self.base_url());
-        
-        let response = self
-            .http_client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.api_key()))
-            .header("X-Workspace", self.workspace())
-            .header("Content-Type", "application/json")
-            .json(pipeline)
-            .send()
-            .await
-            .context("Failed to push pipeline")?;
+        let request = |url: &str| {
+            self.authorized(self.http_client.post(url))
+                .header("X-Workspace", self.workspace())
+                .header("Content-Type", "application/json")
+                .json(pipeline)
+        };
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            anyhow::bail!("Pipeline push failed: {} - {}", status, error_text);
-        }
+        let response = self.execute("/pipelines", request).await?;
 
         let result: PipelineResponse = response
             .json()
             .await
-            .context("Failed to parse pipeline response")?;
+            .map_err(|e| Error::Decode(format!("pipeline response: {}", e)))?;
 
         Ok(result)
     }
+
+    /// Push a pipeline as its original YAML so the server can validate it directly.
+    ///
+    /// Servers without YAML support answer 415; the parsed `pipeline` is then sent as JSON.
+    #[instrument(skip_all)]
+    pub async fn push_pipeline_yaml(&self, yaml: &str, pipeline: &Value) -> Result<PipelineResponse> {
+        let request = |url: &str| {
+            self.authorized(self.http_client.post(url))
+                .header("X-Workspace", self.workspace())
+                .header("Content-Type", "application/yaml")
+                .body(yaml.to_string())
+        };
+
+        let response = match self.execute("/pipelines", request).await {
+            Err(Error::Server { status: 415, .. }) => {
+                warn!("Server does not accept YAML pipelines, falling back to JSON");
+                return self.push_pipeline(pipeline).await;
+            }
+            result => result?,
+        };
+
+        response
+            .json()
+            .await
+            .map_err(|e| Error::Decode(format!("pipeline response: {}", e)))
+    }
+
+    /// List pipelines deployed in the workspace
+    pub async fn list_pipelines(&self) -> Result<Vec<PipelineResponse>> {
+        let request = |url: &str| {
+            self.authorized(self.http_client.get(url))
+                .header("X-Workspace", self.workspace())
+        };
+
+        let response = self.execute("/pipelines", request).await?;
+
+        response
+            .json()
+            .await
+            .map_err(|e| Error::Decode(format!("pipeline list: {}", e)))
+    }
+
+    /// Fetch the deployed definition of a pipeline
+    pub async fn get_pipeline(&self, id: &str) -> Result<Value> {
+        let path = format!("/pipelines/{}", id);
+        let request = |url: &str| {
+            self.authorized(self.http_client.get(url))
+                .header("X-Workspace", self.workspace())
+        };
+
+        let response = self.execute(&path, request).await?;
+
+        response
+            .json()
+            .await
+            .map_err(|e| Error::Decode(format!("pipeline definition: {}", e)))
+    }
+
+    /// Fetch the most recent runs of a pipeline, newest first
+    pub async fn get_pipeline_runs(&self, id: &str, limit: usize) -> Result<Vec<PipelineRun>> {
+        let path = format!("/pipelines/{}/runs?limit={}", id, limit);
+        let request = |url: &str| {
+            self.authorized(self.http_client.get(url))
+                .header("X-Workspace", self.workspace())
+        };
+
+        let response = self.execute(&path, request).await?;
+
+        response
+            .json()
+            .await
+            .map_err(|e| Error::Decode(format!("pipeline runs: {}", e)))
+    }
+
+    /// Fetch how many events the workspace has ingested against its quota
+    pub async fn get_usage(&self) -> Result<Usage> {
+        let request = |url: &str| {
+            self.authorized(self.http_client.get(url))
+                .header("X-Workspace", self.workspace())
+        };
+
+        let response = self.execute("/usage", request).await?;
+
+        response
+            .json()
+            .await
+            .map_err(|e| Error::Decode(format!("usage: {}", e)))
+    }
+
+    /// Send a request to `path` (relative to the API prefix), failing over across
+    /// endpoints on network and 5xx errors.
+    ///
+    /// Starts from the last endpoint that answered so a healthy fallback stays in use.
+    async fn execute<F>(&self, path: &str, build: F) -> Result<Response>
+    where
+        F: Fn(&str) -> RequestBuilder,
+    {
+        let endpoints = self.config.endpoints();
+        let prefix = self.config.api_prefix();
+        let start = self.active_endpoint.load(Ordering::Relaxed);
+        let mut last_error = None;
+
+        for offset in 0..endpoints.len() {
+            let index = (start + offset) % endpoints.len();
+            let url = format!("{}{}{}", endpoints[index], prefix, path);
+            let mut request = build(&url);
+            for (name, value) in &self.config.extra_headers {
+                request = request.header(name, value);
+            }
+
+            match self.send(request).await {
+                Err(err) if is_failover_error(&err) => {
+                    if endpoints.len() > 1 {
+                        warn!("Endpoint {} unavailable: {}", endpoints[index], err);
+                    }
+                    last_error = Some(err);
+                }
+                result => {
+                    if index != start {
+                        warn!("Failed over to endpoint {}", endpoints[index]);
+                        self.active_endpoint.store(index, Ordering::Relaxed);
+                    }
+                    return result;
+                }
+            }
+        }
+
+        Err(last_error.expect("config always has at least one endpoint"))
+    }
+
+    /// Send a single request, logging it at debug level, and fail on non-success statuses
+    async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        self.check_env_headers()?;
+        let request = request.build().map_err(Error::Network)?;
+        let host = request.url().host_str().map(str::to_string);
+
+        if enabled!(Level::DEBUG) {
+            let body_size = request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .map_or(0, |bytes| bytes.len());
+            debug!(
+                "{} {} ({} bytes) headers: {}",
+                request.method(),
+                request.url(),
+                body_size,
+                redact_headers(request.headers(), &self.secret_headers())
+            );
+        }
+
+        let dump = self
+            .print_requests
+            .then(|| render_request(&request, &self.secret_headers()))
+            .flatten();
+        let printed = dump.as_ref().is_some_and(|dump| {
+            let first = !self.request_printed.swap(true, Ordering::Relaxed);
+            if first {
+                eprintln!("{}", dump);
+            }
+            first
+        });
+
+        let result = self.dispatch(request, host).await;
+        if let (Err(_), Some(dump), false) = (&result, &dump, printed) {
+            eprintln!("{}", dump);
+        }
+        result
+    }
+
+    /// Execute a built request and fail on non-success statuses
+    async fn dispatch(&self, request: Request, host: Option<String>) -> Result<Response> {
+        let response = match self.http_client.execute(request).await {
+            Ok(response) => response,
+            Err(e) => return Err(network_error(e, host).await),
+        };
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let request_id = ["x-request-id", "x-trace-id"]
+                .iter()
+                .find_map(|name| response.headers().get(*name))
+                .and_then(|value| value.to_str().ok())
+                .map(String::from);
+            let body = response.text().await.unwrap_or_default();
+            debug!("Response {}: {}", status, body);
+            return Err(Error::from_status(status, body, request_id));
+        }
+
+        debug!("Response {}", response.status());
+        Ok(response)
+    }
 }
 
-#[derive(Debug, serde::Deserialize)]
+/// Parse a gateway-reported version, tolerating a leading `v`
+fn parse_server_version(version: &str) -> Option<semver::Version> {
+    semver::Version::parse(version.trim().trim_start_matches('v')).ok()
+}
+
+/// Encode events as the chunks of a JSON array: `[`, then each event (comma-prefixed
+/// after the first), then `]`
+fn json_array_body<S>(events: S) -> impl Stream<Item = std::io::Result<Vec<u8>>>
+where
+    S: Stream<Item = Value>,
+{
+    let elements = events.enumerate().map(|(index, event)| {
+        let mut chunk = Vec::with_capacity(256);
+        if index > 0 {
+            chunk.push(b',');
+        }
+        serde_json::to_writer(&mut chunk, &event)?;
+        Ok(chunk)
+    });
+
+    stream::once(async { Ok(b"[".to_vec()) })
+        .chain(elements)
+        .chain(stream::once(async { Ok(b"]".to_vec()) }))
+}
+
+/// Whether an error suggests the endpoint itself is down rather than the request being bad
+fn is_failover_error(err: &Error) -> bool {
+    match err {
+        Error::Network(_) | Error::UnresolvedHost { .. } => true,
+        Error::Server { status, .. } => *status >= 500,
+        _ => false,
+    }
+}
+
+/// Whether a failed request is worth trying again shortly: refused connections and
+/// failed lookups, as the resolver can't reliably tell a missing host from a blip.
+/// Timeouts aren't retried, as each attempt would wait out the full timeout again
+fn is_transient_network_error(err: &Error) -> bool {
+    match err {
+        Error::Network(e) => e.is_connect() && !e.is_timeout(),
+        Error::UnresolvedHost { .. } => true,
+        _ => false,
+    }
+}
+
+/// Wrap a transport error, turning a failed connection to a host that doesn't
+/// resolve into `Error::UnresolvedHost`
+async fn network_error(err: reqwest::Error, host: Option<String>) -> Error {
+    match host {
+        Some(host) if err.is_connect() && !err.is_timeout() && !resolves(&host).await => {
+            Error::UnresolvedHost { host }
+        }
+        _ => Error::Network(err),
+    }
+}
+
+/// Whether `host` resolves to at least one address; only consulted after a failed
+/// connection, since reqwest doesn't expose a typed cause for resolver errors
+async fn resolves(host: &str) -> bool {
+    tokio::net::lookup_host((host, 0))
+        .await
+        .is_ok_and(|mut addrs| addrs.next().is_some())
+}
+
+/// Batch id from `X-Batch-Id`, or a `batch_id` field when the body is JSON
+async fn batch_id(response: Response) -> Option<String> {
+    let header = response
+        .headers()
+        .get("x-batch-id")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    if header.is_some() {
+        return header;
+    }
+
+    let body: Value = response.json().await.ok()?;
+    body.get("batch_id").and_then(Value::as_str).map(str::to_string)
+}
+
+/// Render headers for logging with credentials, including the configured auth
+/// header, replaced
+fn redact_headers(headers: &HeaderMap, secret_headers: &[&str]) -> String {
+    headers
+        .iter()
+        .map(|(name, value)| redact_header(name, value, secret_headers))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn redact_header(name: &HeaderName, value: &HeaderValue, secret_headers: &[&str]) -> String {
+    if SENSITIVE_HEADERS.contains(&name.as_str())
+        || secret_headers
+            .iter()
+            .any(|secret| name.as_str().eq_ignore_ascii_case(secret))
+    {
+        format!("{}: <redacted>", name)
+    } else {
+        format!("{}: {}", name, value.to_str().unwrap_or("<binary>"))
+    }
+}
+
+/// Render a request for `with_request_printing`, with gzip bodies decompressed;
+/// `None` when it has no buffered body
+fn render_request(request: &Request, secret_headers: &[&str]) -> Option<String> {
+    let bytes = request.body()?.as_bytes()?;
+    let headers = request.headers();
+
+    let gzipped = headers
+        .get("content-encoding")
+        .is_some_and(|value| value.as_bytes() == b"gzip");
+    let mut decompressed = Vec::new();
+    let bytes = if gzipped
+        && flate2::read::GzDecoder::new(bytes)
+            .read_to_end(&mut decompressed)
+            .is_ok()
+    {
+        decompressed.as_slice()
+    } else {
+        bytes
+    };
+    let body = match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => format!("<{} bytes of binary data>", bytes.len()),
+    };
+
+    let mut rendered = format!("> {} {}\n", request.method(), request.url());
+    for (name, value) in headers {
+        rendered.push_str(&format!("> {}\n", redact_header(name, value, secret_headers)));
+    }
+    rendered.push_str(">\n");
+    rendered.push_str(&body);
+    Some(rendered)
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
 pub struct HealthStatus {
     pub status: String,
     pub version: Option<String>,
@@ -173,13 +927,29 @@ pub struct HealthStatus {
     pub metrics: Option<HealthMetrics>,
 }
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize)]
 pub struct HealthMetrics {
     pub requests_total: Option<u64>,
     pub requests_per_second: Option<f64>,
     pub latency_ms: Option<f64>,
 }
 
+impl HealthMetrics {
+    /// Whether the gateway sent a `metrics` object without any of the known fields
+    pub fn is_empty(&self) -> bool {
+        self.requests_total.is_none()
+            && self.requests_per_second.is_none()
+            && self.latency_ms.is_none()
+    }
+}
+
+impl HealthStatus {
+    /// The metrics, unless the gateway omitted them or reported none we know
+    pub fn reported_metrics(&self) -> Option<&HealthMetrics> {
+        self.metrics.as_ref().filter(|metrics| !metrics.is_empty())
+    }
+}
+
 #[derive(Debug, serde::Deserialize)]
 pub struct PipelineResponse {
     pub id: String,
@@ -188,3 +958,255 @@ pub struct PipelineResponse {
     pub status: String,
 }
 
+/// Processing state of a batch the gateway accepted for asynchronous processing
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct BatchStatus {
+    pub status: String,
+    #[serde(default)]
+    pub processed: u64,
+    #[serde(default)]
+    pub rejected: u64,
+    /// Why individual events were rejected, when the gateway says
+    #[serde(default)]
+    pub errors: Vec<RejectedEvent>,
+}
+
+impl BatchStatus {
+    /// Whether the gateway is done with the batch (anything but pending/queued/processing)
+    pub fn is_terminal(&self) -> bool {
+        !matches!(self.status.as_str(), "pending" | "queued" | "processing")
+    }
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct RejectedEvent {
+    /// Position of the event in its batch
+    pub index: Option<usize>,
+    pub error: String,
+}
+
+/// Events ingested by the workspace in the current quota period
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Usage {
+    pub events_used: u64,
+    /// Events allowed per period; absent when the workspace is unlimited
+    pub quota: Option<u64>,
+    /// When the period ends and `events_used` starts over (RFC 3339)
+    pub resets_at: Option<String>,
+}
+
+impl Usage {
+    /// Events left before the quota is reached, if there is one
+    pub fn remaining(&self) -> Option<u64> {
+        self.quota.map(|quota| quota.saturating_sub(self.events_used))
+    }
+
+    /// Share of the quota consumed, from 0.0 (may exceed 1.0 when over quota)
+    pub fn fraction_used(&self) -> Option<f64> {
+        self.quota
+            .map(|quota| self.events_used as f64 / quota.max(1) as f64)
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct PipelineRun {
+    pub id: String,
+    pub status: String,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+    #[serde(default)]
+    pub events_processed: u64,
+    #[serde(default)]
+    pub events_failed: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_redact_headers_hides_api_key() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "Authorization",
+            HeaderValue::from_static("Bearer test_key_12345678"),
+        );
+        headers.insert("X-Workspace", HeaderValue::from_static("test-workspace"));
+        headers.insert("X-Gateway-Token", HeaderValue::from_static("test_key_87654321"));
+
+        let rendered = redact_headers(&headers, &["X-Gateway-Token"]);
+        assert!(!rendered.contains("test_key_12345678"));
+        assert!(!rendered.contains("test_key_87654321"));
+        assert!(rendered.contains("authorization: <redacted>"));
+        assert!(rendered.contains("x-workspace: test-workspace"));
+    }
+
+    #[test]
+    fn test_health_cache_expires() {
+        let health = HealthStatus {
+            status: "healthy".to_string(),
+            version: Some("1.4.0".to_string()),
+            uptime: None,
+            metrics: None,
+        };
+
+        let cache = HealthCache::new(Duration::from_secs(60));
+        assert!(cache.get().is_none());
+        cache.put(&health);
+        assert_eq!(cache.get().unwrap().version.as_deref(), Some("1.4.0"));
+
+        let cache = HealthCache::new(Duration::ZERO);
+        cache.put(&health);
+        assert!(cache.get().is_none());
+    }
+
+    #[test]
+    fn test_msgpack_batch_is_smaller_than_json() {
+        let events: Vec<Value> = (0..500)
+            .map(|i| {
+                serde_json::json!({
+                    "event_type": "sensor_reading",
+                    "timestamp": 1_700_000_000 + i,
+                    "data": {"robot_id": format!("robot-{}", i % 8), "value": i as f64 * 0.5, "ok": true}
+                })
+            })
+            .collect();
+
+        let json = Encoding::Json.encode(&events).unwrap();
+        let msgpack = Encoding::Msgpack.encode(&events).unwrap();
+        assert!(
+            msgpack.len() < json.len(),
+            "json: {} bytes, msgpack: {} bytes",
+            json.len(),
+            msgpack.len()
+        );
+
+        let decoded: Vec<Value> = rmp_serde::from_slice(&msgpack).unwrap();
+        assert_eq!(decoded, events);
+    }
+
+    #[tokio::test]
+    async fn test_json_array_body_is_valid_json() {
+        let events = vec![serde_json::json!({"a": 1}), serde_json::json!({"a": 2})];
+        let chunks: Vec<Vec<u8>> = json_array_body(stream::iter(events.clone()))
+            .map(|chunk| chunk.unwrap())
+            .collect()
+            .await;
+        assert_eq!(chunks.len(), 4);
+
+        let body: Value = serde_json::from_slice(&chunks.concat()).unwrap();
+        assert_eq!(body, Value::Array(events));
+
+        let empty: Vec<Vec<u8>> = json_array_body(stream::iter(Vec::<Value>::new()))
+            .map(|chunk| chunk.unwrap())
+            .collect()
+            .await;
+        assert_eq!(empty.concat(), b"[]");
+    }
+
+    #[test]
+    fn test_parse_server_version() {
+        assert_eq!(
+            parse_server_version("v1.4.2"),
+            Some(semver::Version::new(1, 4, 2))
+        );
+        assert!(parse_server_version("1.10.0").unwrap() > semver::Version::new(1, 9, 0));
+        assert_eq!(parse_server_version("latest"), None);
+    }
+
+    #[test]
+    fn test_env_headers_require_their_variable() {
+        let config = Config::builder()
+            .api_key("test_key_12345678")
+            .workspace("test-workspace")
+            .env_header("X-Proxy-Token", "PYNTHORA_TEST_PROXY_TOKEN_UNSET")
+            .build()
+            .unwrap();
+        let client = Client::new(config).unwrap();
+
+        let err = client.check_env_headers().unwrap_err();
+        assert!(matches!(err, Error::Config(_)));
+        assert!(err.to_string().contains("$PYNTHORA_TEST_PROXY_TOKEN_UNSET"));
+        assert_eq!(client.secret_headers(), ["Authorization", "X-Proxy-Token"]);
+    }
+
+    #[test]
+    fn test_batch_status_is_terminal() {
+        let status: BatchStatus = serde_json::from_value(serde_json::json!({
+            "status": "completed",
+            "processed": 98,
+            "rejected": 2,
+            "errors": [{"index": 4, "error": "unknown sensor"}, {"error": "bad timestamp"}]
+        }))
+        .unwrap();
+        assert!(status.is_terminal());
+        assert_eq!(status.errors[0].index, Some(4));
+        assert_eq!(status.errors[1].index, None);
+
+        let status: BatchStatus =
+            serde_json::from_value(serde_json::json!({"status": "processing"})).unwrap();
+        assert!(!status.is_terminal());
+        assert_eq!(status.processed, 0);
+    }
+
+    #[test]
+    fn test_usage_remaining() {
+        let usage: Usage = serde_json::from_value(serde_json::json!({
+            "events_used": 1200,
+            "quota": 1000,
+            "resets_at": "2026-11-01T00:00:00Z"
+        }))
+        .unwrap();
+        assert_eq!(usage.remaining(), Some(0));
+        assert_eq!(usage.fraction_used(), Some(1.2));
+
+        let unlimited: Usage =
+            serde_json::from_value(serde_json::json!({"events_used": 5})).unwrap();
+        assert_eq!(unlimited.remaining(), None);
+        assert_eq!(unlimited.fraction_used(), None);
+    }
+
+    #[test]
+    fn test_empty_health_metrics_are_not_reported() {
+        let parse = |body| serde_json::from_value::<HealthStatus>(body).unwrap();
+
+        assert!(parse(serde_json::json!({"status": "healthy"})).reported_metrics().is_none());
+        let empty = parse(serde_json::json!({"status": "healthy", "metrics": {}}));
+        assert!(empty.reported_metrics().is_none());
+        let partial = parse(serde_json::json!({"status": "ok", "metrics": {"latency_ms": 4.5}}));
+        assert_eq!(partial.reported_metrics().unwrap().latency_ms, Some(4.5));
+    }
+
+    #[tokio::test]
+    async fn test_unresolved_host_is_retried_then_reported() {
+        let config = Config::builder()
+            .api_key("test_key_12345678")
+            .workspace("test-workspace")
+            .ingest_url("http://gateway.invalid")
+            .build()
+            .unwrap();
+        let client = Client::new(config).unwrap();
+
+        let err = client.health_check(true).await.unwrap_err();
+        assert!(is_transient_network_error(&err));
+        assert_eq!(err.to_string(), "could not resolve host gateway.invalid — check ingest_url");
+    }
+
+    #[tokio::test]
+    async fn test_refused_connection_is_not_an_unresolved_host() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        let config = Config::builder()
+            .api_key("test_key_12345678")
+            .workspace("test-workspace")
+            .ingest_url(format!("http://{}", addr))
+            .build()
+            .unwrap();
+        let client = Client::new(config).unwrap();
+
+        let err = client.ping().await.unwrap_err();
+        assert!(matches!(err, Error::Network(_)), "{:?}", err);
+    }
+}
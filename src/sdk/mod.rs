@@ -1,6 +1,7 @@
 pub mod client;
 pub mod pipelines;
 pub mod signing;
+pub mod streaming;
 pub mod websocket;
 
 
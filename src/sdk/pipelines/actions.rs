@@ -0,0 +1,120 @@
+//! Typed view of the common step actions and their configs
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// A step's action with its config checked against the shape the gateway expects.
+///
+/// Actions the CLI doesn't know are kept as `Custom`, so newer gateway actions still
+/// parse; their config is passed through unchecked.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    Filter(FilterConfig),
+    Map(MapConfig),
+    Enrich(EnrichConfig),
+    Route(RouteConfig),
+    Drop(DropConfig),
+    Custom { action: String, config: Value },
+}
+
+impl Action {
+    /// Type `config` according to `action`
+    pub fn parse(action: &str, config: &Value) -> Result<Self, serde_json::Error> {
+        let config = config.clone();
+        Ok(match action {
+            "filter" => Action::Filter(serde_json::from_value(config)?),
+            "map" => Action::Map(serde_json::from_value(config)?),
+            "enrich" => Action::Enrich(serde_json::from_value(config)?),
+            "route" => Action::Route(serde_json::from_value(config)?),
+            "drop" => Action::Drop(serde_json::from_value(config)?),
+            _ => Action::Custom {
+                action: action.to_string(),
+                config,
+            },
+        })
+    }
+
+    /// The `action` string this was parsed from
+    pub fn name(&self) -> &str {
+        match self {
+            Action::Filter(_) => "filter",
+            Action::Map(_) => "map",
+            Action::Enrich(_) => "enrich",
+            Action::Route(_) => "route",
+            Action::Drop(_) => "drop",
+            Action::Custom { action, .. } => action,
+        }
+    }
+}
+
+/// Keep only events matching `condition`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FilterConfig {
+    /// Expression evaluated per event, e.g. `data.temperature < 100`
+    pub condition: String,
+}
+
+/// Rename or compute fields
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MapConfig {
+    /// Target field → source path or expression
+    pub fields: Map<String, Value>,
+}
+
+/// Join fields from a lookup source onto each event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnrichConfig {
+    /// Lookup table or service to read from
+    pub source: String,
+    /// Event field used as the lookup key
+    pub key: String,
+    /// Fields to copy from the match; all of them when empty
+    #[serde(default)]
+    pub fields: Vec<String>,
+}
+
+/// Send events to other pipelines by the first matching rule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteConfig {
+    pub routes: Vec<RouteRule>,
+    /// Pipeline for events no rule matches; they are dropped when absent
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteRule {
+    pub condition: String,
+    pub pipeline: String,
+}
+
+/// Remove fields from each event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DropConfig {
+    pub fields: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_known_actions_are_typed() {
+        let action = Action::parse("filter", &json!({"condition": "data.ok"})).unwrap();
+        assert_eq!(
+            action,
+            Action::Filter(FilterConfig {
+                condition: "data.ok".to_string()
+            })
+        );
+
+        let error = Action::parse("enrich", &json!({"source": "sites"})).unwrap_err();
+        assert!(error.to_string().contains("key"), "{}", error);
+    }
+
+    #[test]
+    fn test_unknown_actions_are_kept() {
+        let action = Action::parse("geo-fence", &json!({"radius": 5})).unwrap();
+        assert_eq!(action.name(), "geo-fence");
+        assert!(matches!(action, Action::Custom { config, .. } if config["radius"] == 5));
+    }
+}
@@ -1,18 +1,115 @@
+pub mod actions;
+
+pub use actions::Action;
+
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::path::Path;
 
+/// A pipeline definition; fields the CLI doesn't model (`metadata`, `schedule`, ...)
+/// are kept in `extra` so parsing and re-serializing doesn't drop them
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PipelineDefinition {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
     pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     pub steps: Vec<PipelineStep>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
+/// A pipeline step. Deserializing checks `config` against the shape of known actions
+/// (see [`Action`]); unknown actions are accepted as they are
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "RawStep")]
 pub struct PipelineStep {
     pub name: String,
     pub action: String,
-    pub config: serde_json::Value,
+    pub config: Value,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl PipelineStep {
+    /// The action with its config typed
+    pub fn typed_action(&self) -> Result<Action, serde_json::Error> {
+        Action::parse(&self.action, &self.config)
+    }
+}
+
+/// A step as written, before its config is checked
+#[derive(Deserialize)]
+struct RawStep {
+    name: String,
+    action: String,
+    config: Value,
+    #[serde(flatten)]
+    extra: Map<String, Value>,
+}
+
+impl TryFrom<RawStep> for PipelineStep {
+    type Error = String;
+
+    fn try_from(raw: RawStep) -> Result<Self, Self::Error> {
+        let step = PipelineStep {
+            name: raw.name,
+            action: raw.action,
+            config: raw.config,
+            extra: raw.extra,
+        };
+        step.typed_action().map_err(|e| {
+            format!("step '{}': invalid config for '{}': {}", step.name, step.action, e)
+        })?;
+        Ok(step)
+    }
+}
+
+/// Languages a pipeline definition can be written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Syntax {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl Syntax {
+    /// The syntax implied by a `.json`, `.yaml`/`.yml` or `.toml` extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "json" => Some(Syntax::Json),
+            "yaml" | "yml" => Some(Syntax::Yaml),
+            "toml" => Some(Syntax::Toml),
+            _ => None,
+        }
+    }
+
+    /// The syntax of `content` at `path`: the extension when it's known, otherwise JSON
+    /// if the content parses as (or clearly starts like) JSON, and YAML if not
+    pub fn detect(path: &Path, content: &str) -> Self {
+        if let Some(syntax) = Self::from_path(path) {
+            return syntax;
+        }
+        let looks_like_json = content.trim_start().starts_with(['{', '[']);
+        if looks_like_json || serde_json::from_str::<Value>(content).is_ok() {
+            Syntax::Json
+        } else {
+            Syntax::Yaml
+        }
+    }
+}
+
+impl fmt::Display for Syntax {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Syntax::Json => "JSON",
+            Syntax::Yaml => "YAML",
+            Syntax::Toml => "TOML",
+        })
+    }
 }
 
 pub fn parse_yaml(content: &str) -> anyhow::Result<PipelineDefinition> {
@@ -23,3 +120,78 @@ pub fn parse_json(content: &str) -> anyhow::Result<PipelineDefinition> {
     serde_json::from_str(content).map_err(Into::into)
 }
 
+pub fn parse_toml(content: &str) -> anyhow::Result<PipelineDefinition> {
+    toml::from_str(content).map_err(Into::into)
+}
+
+/// Parse `content` written in `syntax`, as a definition or as a raw `serde_json::Value`
+pub fn parse_as<T: DeserializeOwned>(syntax: Syntax, content: &str) -> anyhow::Result<T> {
+    match syntax {
+        Syntax::Json => serde_json::from_str(content).map_err(Into::into),
+        Syntax::Yaml => serde_yaml::from_str(content).map_err(Into::into),
+        Syntax::Toml => toml::from_str(content).map_err(Into::into),
+    }
+}
+
+/// Parse `content` in the syntax picked by [`Syntax::detect`] from `path`'s extension
+pub fn parse_auto<T: DeserializeOwned>(path: &Path, content: &str) -> anyhow::Result<T> {
+    parse_as(Syntax::detect(path, content), content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOML_PIPELINE: &str = r#"
+name = "sensors"
+
+[[steps]]
+name = "drop-noise"
+action = "filter"
+config = { condition = "data.temperature < 100" }
+"#;
+
+    #[test]
+    fn test_parse_toml() {
+        let pipeline = parse_toml(TOML_PIPELINE).unwrap();
+        assert_eq!(pipeline.name, "sensors");
+        assert!(matches!(pipeline.steps[0].typed_action().unwrap(), Action::Filter(_)));
+    }
+
+    #[test]
+    fn test_parse_auto_dispatches_on_extension() {
+        let pipeline: PipelineDefinition =
+            parse_auto(Path::new("sensors.toml"), TOML_PIPELINE).unwrap();
+        assert_eq!(pipeline.steps.len(), 1);
+
+        let yaml = "name: sensors\nsteps: []\n";
+        let pipeline: PipelineDefinition = parse_auto(Path::new("sensors.yml"), yaml).unwrap();
+        assert_eq!(pipeline.name, "sensors");
+
+        assert_eq!(Syntax::detect(Path::new("-"), "{\"name\": 1}"), Syntax::Json);
+        assert_eq!(Syntax::detect(Path::new("-"), yaml), Syntax::Yaml);
+        assert!(parse_auto::<PipelineDefinition>(Path::new("p.json"), yaml).is_err());
+    }
+
+    #[test]
+    fn test_unknown_fields_round_trip() {
+        let original = serde_json::json!({
+            "name": "sensors",
+            "schedule": "*/5 * * * *",
+            "metadata": {"owner": "data-eng"},
+            "steps": [{"name": "s1", "action": "drop", "config": {"fields": []}, "retries": 3}]
+        });
+
+        let pipeline = parse_json(&original.to_string()).unwrap();
+        assert_eq!(pipeline.extra["schedule"], "*/5 * * * *");
+        assert_eq!(pipeline.steps[0].extra["retries"], 3);
+        assert_eq!(serde_json::to_value(&pipeline).unwrap(), original);
+    }
+
+    #[test]
+    fn test_malformed_step_config_fails_to_parse() {
+        let yaml = "name: p\nsteps:\n  - name: keep-ok\n    action: filter\n    config: {if: x}\n";
+        let error = parse_yaml(yaml).unwrap_err().to_string();
+        assert!(error.contains("step 'keep-ok': invalid config for 'filter'"), "{}", error);
+    }
+}
@@ -1,29 +1,78 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use ed25519_dalek::pkcs8::DecodePrivateKey;
+use ed25519_dalek::Signer;
+pub use ed25519_dalek::SigningKey;
 use pynthora_terminal::core::telemetry::TelemetryEvent;
 use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
 
-/// Sign a telemetry event for ZK-proof generation
-pub fn sign_event(event: &TelemetryEvent) -> Result<String> {
-    // TODO: Implement actual cryptographic signing
-    // For now, return a mock signature
+/// Field that `sign_value` attaches the hex-encoded signature under
+pub const SIGNATURE_FIELD: &str = "signature";
+
+/// Load an Ed25519 signing key from a PKCS#8 PEM file or a hex-encoded 32-byte seed
+pub fn load_signing_key(path: &Path) -> Result<SigningKey> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read signing key: {}", path.display()))?;
+    let content = content.trim();
+
+    if content.starts_with("-----BEGIN") {
+        return SigningKey::from_pkcs8_pem(content)
+            .map_err(|e| anyhow::anyhow!("Invalid PKCS#8 signing key {}: {}", path.display(), e));
+    }
+
+    let seed: [u8; 32] = hex::decode(content)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .with_context(|| {
+            format!(
+                "Signing key {} must be PKCS#8 PEM or a 64-character hex seed",
+                path.display()
+            )
+        })?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+/// Sign a telemetry event for ZK-proof generation, returning a hex Ed25519 signature.
+///
+/// The signed payload covers the event type, timestamp and a SHA-256 of the data;
+/// metadata is left out so it can be enriched after signing.
+pub fn sign_event(event: &TelemetryEvent, key: &SigningKey) -> Result<String> {
     let signature_data = json!({
         "event_type": event.event_type,
         "timestamp": event.timestamp,
         "data_hash": hash_data(&event.data),
     });
 
-    Ok(serde_json::to_string(&signature_data)?)
+    let payload = serde_json::to_vec(&signature_data)?;
+    Ok(hex::encode(key.sign(&payload).to_bytes()))
 }
 
+/// Sign a raw event and attach the signature under `signature`.
+///
+/// Fails when the event doesn't have the shape of a `TelemetryEvent` (numeric
+/// `timestamp`, string `event_type`, `data`).
+pub fn sign_value(event: &mut serde_json::Value, key: &SigningKey) -> Result<()> {
+    let telemetry: TelemetryEvent = serde_json::from_value(event.clone())
+        .context("event can't be signed: expected numeric 'timestamp', 'event_type' and 'data'")?;
+    let signature = sign_event(&telemetry, key)?;
+
+    if let Some(obj) = event.as_object_mut() {
+        obj.insert(SIGNATURE_FIELD.to_string(), serde_json::Value::String(signature));
+    }
+    Ok(())
+}
+
+/// Hex-encoded SHA-256 of the data's JSON serialization
 fn hash_data(data: &serde_json::Value) -> String {
-    // TODO: Implement actual hashing (e.g., SHA-256)
-    // For now, return a placeholder
-    format!("hash_{}", data.to_string().len())
+    hex::encode(Sha256::digest(data.to_string().as_bytes()))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ed25519_dalek::{Signature, Verifier};
     use pynthora_terminal::core::telemetry::TelemetryEvent;
 
     #[test]
@@ -32,9 +81,33 @@ mod tests {
             "test_event",
             serde_json::json!({"key": "value"}),
         );
+        let key = SigningKey::from_bytes(&[7u8; 32]);
 
-        let signature = sign_event(&event).unwrap();
+        let signature = sign_event(&event, &key).unwrap();
         assert!(!signature.is_empty());
+
+        let payload = serde_json::to_vec(&json!({
+            "event_type": event.event_type,
+            "timestamp": event.timestamp,
+            "data_hash": hash_data(&event.data),
+        }))
+        .unwrap();
+        let bytes: [u8; 64] = hex::decode(&signature).unwrap().try_into().unwrap();
+        assert!(key
+            .verifying_key()
+            .verify(&payload, &Signature::from_bytes(&bytes))
+            .is_ok());
     }
-}
 
+    #[test]
+    fn test_sign_value_rejects_non_telemetry_events() {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+
+        let mut event = serde_json::json!({"timestamp": 1, "event_type": "a", "data": {}});
+        sign_value(&mut event, &key).unwrap();
+        assert!(event[SIGNATURE_FIELD].is_string());
+
+        let mut event = serde_json::json!({"timestamp": "yesterday", "data": {}});
+        assert!(sign_value(&mut event, &key).is_err());
+    }
+}
@@ -0,0 +1,734 @@
+//! Batching stream engine shared by the CLI and embedding services
+use futures_util::future;
+use futures_util::stream::{self, FuturesUnordered, Stream, StreamExt};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::pin::pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tokio::time::sleep_until;
+use tracing::{debug, instrument, warn};
+
+use crate::core::dead_letter::DeadLetter;
+use crate::core::journal::Journal;
+use crate::core::error::{Error, Result};
+use crate::core::retry::{retry_with_backoff_notify, RetryConfig};
+use crate::core::sent_log::SentLog;
+use crate::core::validation::{validate_batch, BatchLimits};
+use crate::sdk::client::Client;
+
+/// Receives progress from `Client::stream_all`, e.g. to drive a progress bar or GUI.
+///
+/// All methods default to no-ops so implementors only override what they display.
+pub trait ProgressReporter: Send + Sync {
+    /// A batch finished (successfully or not); `done` of `total` queued events are settled
+    fn on_batch_complete(&self, _done: usize, _total: usize) {}
+
+    /// A batch failed after exhausting its retries
+    fn on_error(&self, _batch_num: usize, _error: &str) {}
+
+    /// A batch is about to be retried
+    fn on_retry(&self, _batch_num: usize, _attempt: u32, _max_attempts: u32) {}
+
+    /// A batch settled after `latency`, including retries and 413 splits
+    fn on_batch_latency(&self, _batch_num: usize, _latency: Duration) {}
+}
+
+/// Reporter that ignores all progress, for library use without a UI
+pub struct NoopReporter;
+
+impl ProgressReporter for NoopReporter {}
+
+/// Options controlling how `Client::stream_all` batches and sends events
+#[derive(Clone)]
+pub struct StreamOptions {
+    /// Pipeline ID sent with every batch
+    pub pipeline: Option<String>,
+    /// Event field naming each event's pipeline; events are grouped by it (and the
+    /// field stripped) before sending, falling back to `pipeline` when absent
+    pub pipeline_field: Option<String>,
+    /// Workspace to send to instead of the configured one
+    pub workspace: Option<String>,
+    /// Maximum events per batch
+    pub batch_size: usize,
+    /// Number of batches allowed in flight at once
+    pub concurrency: usize,
+    /// Stop dispatching new batches while the serialized size of batches in flight is
+    /// at or above this many bytes, bounding memory whatever the concurrency and
+    /// event size (a single batch may still exceed it)
+    pub max_in_flight_bytes: Option<usize>,
+    /// Retry policy applied to each batch
+    pub retry: RetryConfig,
+    /// Validate each batch before sending it
+    pub validate: bool,
+    /// Limits used when validating batches
+    pub limits: BatchLimits,
+    /// Receives batch completion, retry and error notifications
+    pub progress: Arc<dyn ProgressReporter>,
+    /// Set to `true` to stop reading new events (e.g. on Ctrl+C); batches already
+    /// formed are still sent
+    pub shutdown: Option<watch::Receiver<bool>>,
+    /// After shutdown, how long to wait for in-flight batches before abandoning them
+    pub drain_timeout: Duration,
+    /// Stop reading new events once a batch fails after its retries; batches already
+    /// in flight still finish
+    pub abort_on_failure: bool,
+    /// Where events that fail after retries, or are abandoned at shutdown, are written
+    pub dead_letter: Option<Arc<DeadLetter>>,
+    /// Where the keys of delivered events are recorded, to skip them on a rerun
+    pub sent_log: Option<Arc<SentLog>>,
+    /// Where each delivered event is journaled, for reconciling against `dead_letter`
+    pub journal: Option<Arc<Journal>>,
+    /// Tune batch size and concurrency from observed latency and errors instead of
+    /// using `batch_size`/`concurrency` as fixed values
+    pub adaptive: Option<AdaptiveConfig>,
+}
+
+impl fmt::Debug for StreamOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamOptions")
+            .field("pipeline", &self.pipeline)
+            .field("pipeline_field", &self.pipeline_field)
+            .field("workspace", &self.workspace)
+            .field("batch_size", &self.batch_size)
+            .field("concurrency", &self.concurrency)
+            .field("max_in_flight_bytes", &self.max_in_flight_bytes)
+            .field("retry", &self.retry)
+            .field("validate", &self.validate)
+            .field("limits", &self.limits)
+            .field("drain_timeout", &self.drain_timeout)
+            .field("abort_on_failure", &self.abort_on_failure)
+            .field("adaptive", &self.adaptive)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for StreamOptions {
+    fn default() -> Self {
+        Self {
+            pipeline: None,
+            pipeline_field: None,
+            workspace: None,
+            batch_size: 100,
+            concurrency: 1,
+            max_in_flight_bytes: None,
+            retry: RetryConfig::default(),
+            validate: true,
+            limits: BatchLimits::default(),
+            progress: Arc::new(NoopReporter),
+            shutdown: None,
+            drain_timeout: Duration::from_secs(30),
+            abort_on_failure: false,
+            dead_letter: None,
+            sent_log: None,
+            journal: None,
+            adaptive: None,
+        }
+    }
+}
+
+/// Bounds for adaptive (AIMD) batching
+#[derive(Debug, Clone)]
+pub struct AdaptiveConfig {
+    pub max_concurrency: usize,
+    pub min_batch_size: usize,
+    pub max_batch_size: usize,
+    /// Batches that complete faster than this, without throttling, let the sender grow
+    pub target_latency: Duration,
+}
+
+impl Default for AdaptiveConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrency: 16,
+            min_batch_size: 10,
+            max_batch_size: 1000,
+            target_latency: Duration::from_millis(500),
+        }
+    }
+}
+
+impl AdaptiveConfig {
+    /// These bounds with batches never growing past `max_events`, a hard cap such as
+    /// `BatchLimits::max_events`
+    pub fn capped(mut self, max_events: Option<usize>) -> Self {
+        if let Some(max) = max_events {
+            self.max_batch_size = self.max_batch_size.min(max);
+            self.min_batch_size = self.min_batch_size.min(self.max_batch_size);
+        }
+        self
+    }
+}
+
+/// Additive-increase/multiplicative-decrease controller for batch size and concurrency.
+///
+/// Starts at one batch in flight; each fast, clean batch adds one slot and grows the
+/// batch, a slow one gives a slot back, and a throttled (429) or failed one halves both.
+#[derive(Debug, Clone)]
+pub struct AdaptiveController {
+    config: AdaptiveConfig,
+    concurrency: usize,
+    batch_size: usize,
+}
+
+impl AdaptiveController {
+    /// Batch size grows by this many events per fast batch
+    const BATCH_STEP: usize = 50;
+
+    pub fn new(config: AdaptiveConfig, initial_batch_size: usize) -> Self {
+        // Not `clamp`, which panics if a caller's bounds cross
+        let batch_size = initial_batch_size
+            .max(config.min_batch_size)
+            .min(config.max_batch_size);
+        Self {
+            config,
+            concurrency: 1,
+            batch_size,
+        }
+    }
+
+    /// Feed back one completed batch
+    pub fn record(&mut self, latency: Duration, healthy: bool) {
+        if !healthy {
+            self.concurrency = (self.concurrency / 2).max(1);
+            self.batch_size = (self.batch_size / 2).max(self.config.min_batch_size);
+        } else if latency <= self.config.target_latency {
+            self.concurrency = (self.concurrency + 1).min(self.config.max_concurrency);
+            self.batch_size = (self.batch_size + Self::BATCH_STEP).min(self.config.max_batch_size);
+        } else {
+            self.concurrency = self.concurrency.saturating_sub(1).max(1);
+        }
+    }
+
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+}
+
+/// How a sent batch went, for the adaptive controller
+struct BatchOutcome {
+    batch_num: usize,
+    /// Each group's events and result, the server's batch id on success
+    groups: Vec<(Vec<Value>, anyhow::Result<Option<String>>)>,
+    latency: Duration,
+    /// Any attempt was answered with 429, or with 413 and had to be split
+    throttled: bool,
+}
+
+/// Aggregate results of a `Client::stream_all` run
+#[derive(Debug, Clone, Default)]
+pub struct StreamStats {
+    pub total: usize,
+    pub successful: usize,
+    pub failed: usize,
+    pub batches: usize,
+    pub elapsed: Duration,
+    /// Whether the run was stopped early through `StreamOptions::shutdown`
+    pub interrupted: bool,
+    /// Events whose batches were still in flight when the drain timeout expired
+    pub unsent: usize,
+    /// Reading stopped early because a batch failed, with `abort_on_failure`
+    pub aborted: bool,
+    /// Why a batch failed validation, which stops reading; that batch is counted as
+    /// failed and never sent, while batches already in flight finish
+    pub validation_error: Option<String>,
+    /// Concurrency in use at the end of the run (the tuned value with `adaptive`)
+    pub final_concurrency: usize,
+    /// Batch size in use at the end of the run (the tuned value with `adaptive`)
+    pub final_batch_size: usize,
+    /// Ids of delivered batches that the gateway is processing asynchronously
+    pub batch_ids: Vec<String>,
+}
+
+impl StreamStats {
+    /// Fold in the stats of a later `stream_all` over the next part of the same input
+    pub fn merge(&mut self, other: StreamStats) {
+        self.total += other.total;
+        self.successful += other.successful;
+        self.failed += other.failed;
+        self.batches += other.batches;
+        self.elapsed += other.elapsed;
+        self.interrupted |= other.interrupted;
+        self.unsent += other.unsent;
+        self.aborted |= other.aborted;
+        self.validation_error = self.validation_error.take().or(other.validation_error);
+        self.final_concurrency = other.final_concurrency;
+        self.final_batch_size = other.final_batch_size;
+        self.batch_ids.extend(other.batch_ids);
+    }
+}
+
+impl Client {
+    /// Batch, validate and send every event from `events`, returning aggregate stats.
+    ///
+    /// Batches that still fail after retries are counted as failed rather than
+    /// aborting the run, unless `opts.abort_on_failure` is set. A batch failing
+    /// validation stops reading and is reported in `StreamStats::validation_error`;
+    /// batches before it may already have been delivered.
+    /// When `opts.shutdown` fires, reading stops, the partial batch is flushed and
+    /// in-flight batches get `opts.drain_timeout` to finish.
+    #[instrument(skip_all, fields(batch_size = opts.batch_size, concurrency = opts.concurrency))]
+    pub async fn stream_all<S>(&self, events: S, opts: StreamOptions) -> Result<StreamStats>
+    where
+        S: Stream<Item = Value>,
+    {
+        let started = Instant::now();
+        let mut stats = StreamStats::default();
+
+        let mut shutdown = opts.shutdown.clone();
+        let stop = async move {
+            match shutdown.as_mut() {
+                Some(rx) => {
+                    // A dropped sender means nobody can ask us to stop
+                    if rx.wait_for(|stop| *stop).await.is_err() {
+                        future::pending::<()>().await;
+                    }
+                }
+                None => future::pending::<()>().await,
+            }
+        };
+        let mut controller = opts
+            .adaptive
+            .clone()
+            .map(|config| AdaptiveController::new(config, opts.batch_size));
+
+        // Like `chunks`, but the size can change between batches
+        let batch_size = AtomicUsize::new(
+            controller
+                .as_ref()
+                .map_or(opts.batch_size, |c| c.batch_size())
+                .max(1),
+        );
+        let mut batches = pin!(stream::unfold(
+            Box::pin(events.take_until(stop)),
+            |mut events| {
+                let size = batch_size.load(Ordering::Relaxed);
+                async move {
+                    let mut batch = Vec::with_capacity(size);
+                    while batch.len() < size {
+                        match events.next().await {
+                            Some(event) => batch.push(event),
+                            None => break,
+                        }
+                    }
+                    (!batch.is_empty()).then_some((batch, events))
+                }
+            }
+        ));
+        let mut concurrency = controller
+            .as_ref()
+            .map_or(opts.concurrency, |c| c.concurrency())
+            .max(1);
+        let mut in_flight = FuturesUnordered::new();
+        // Serialized size of each in-flight batch, tracked only with `max_in_flight_bytes`
+        let mut in_flight_sizes: HashMap<usize, usize> = HashMap::new();
+        let mut in_flight_bytes = 0;
+        let mut exhausted = false;
+        let mut drain_deadline = None;
+        // Watched separately from the event stream so the drain timer starts at the
+        // signal even when every slot is busy and no batch is being read
+        let mut stop_rx = opts.shutdown.clone();
+        let mut watching_stop = stop_rx.is_some();
+
+        // Copies of in-flight batches, kept only so abandoned ones can be dead-lettered
+        let mut pending: HashMap<usize, Vec<Value>> = HashMap::new();
+
+        loop {
+            tokio::select! {
+                batch = batches.next(), if !exhausted
+                    && in_flight.len() < concurrency
+                    && opts.max_in_flight_bytes.is_none_or(|max| in_flight_bytes < max) =>
+                {
+                    let Some(batch) = batch else {
+                        exhausted = true;
+                        if opts.shutdown.as_ref().is_some_and(|rx| *rx.borrow()) {
+                            stats.interrupted = true;
+                            drain_deadline.get_or_insert_with(|| {
+                                tokio::time::Instant::now() + opts.drain_timeout
+                            });
+                        }
+                        continue;
+                    };
+
+                    if opts.validate {
+                        let validation = validate_batch(&batch, &opts.limits);
+                        let batch_num = stats.batches + 1;
+                        for warning in &validation.warnings {
+                            warn!("Batch {}: {}", batch_num, warning);
+                        }
+                        if !validation.is_valid {
+                            // Stop reading, but let in-flight batches finish so they
+                            // are counted, dead-lettered and recorded as usual
+                            let error = validation.errors.join("; ");
+                            warn!("Batch {} failed validation, not sending any more", batch_num);
+                            opts.progress.on_error(batch_num, &error);
+                            stats.total += batch.len();
+                            stats.failed += batch.len();
+                            dead_letter(&opts, &batch, &error);
+                            stats.validation_error = Some(error);
+                            exhausted = true;
+                            continue;
+                        }
+                    }
+
+                    stats.total += batch.len();
+                    stats.batches += 1;
+                    if opts.dead_letter.is_some() {
+                        pending.insert(stats.batches, batch.clone());
+                    }
+                    if opts.max_in_flight_bytes.is_some() {
+                        let size: usize = batch.iter().map(|e| e.to_string().len()).sum();
+                        in_flight_bytes += size;
+                        in_flight_sizes.insert(stats.batches, size);
+                    }
+                    in_flight.push(self.send_with_retry(stats.batches, batch, &opts));
+                }
+                Some(outcome) = in_flight.next() => {
+                    let BatchOutcome { batch_num, groups, latency, throttled } = outcome;
+                    pending.remove(&batch_num);
+                    in_flight_bytes -= in_flight_sizes.remove(&batch_num).unwrap_or(0);
+                    opts.progress.on_batch_latency(batch_num, latency);
+
+                    if let Some(controller) = controller.as_mut() {
+                        let healthy = !throttled && groups.iter().all(|(_, r)| r.is_ok());
+                        controller.record(latency, healthy);
+                        concurrency = controller.concurrency();
+                        batch_size.store(controller.batch_size(), Ordering::Relaxed);
+                        debug!(
+                            "Adaptive: batch {} took {:?} ({}), now concurrency {} batch size {}",
+                            batch_num,
+                            latency,
+                            if healthy { "ok" } else { "degraded" },
+                            concurrency,
+                            controller.batch_size()
+                        );
+                    }
+
+                    for (events, result) in groups {
+                        match result {
+                            Ok(batch_id) => {
+                                stats.successful += events.len();
+                                stats.batch_ids.extend(batch_id);
+                                debug!("Batch {} processed successfully", batch_num);
+                                record_sent(&opts, &events);
+                            }
+                            Err(e) => {
+                                stats.failed += events.len();
+                                warn!("Batch {} failed: {}", batch_num, e);
+                                opts.progress.on_error(batch_num, &e.to_string());
+                                dead_letter(&opts, &events, &e.to_string());
+                                if opts.abort_on_failure && !stats.aborted {
+                                    warn!("Batch {} failed, not sending any more", batch_num);
+                                    stats.aborted = true;
+                                    exhausted = true;
+                                }
+                            }
+                        }
+                    }
+                    opts.progress
+                        .on_batch_complete(stats.successful + stats.failed, stats.total);
+                }
+                stopped = async { stop_rx.as_mut()?.wait_for(|stop| *stop).await.ok() },
+                    if watching_stop && drain_deadline.is_none() && !in_flight.is_empty() =>
+                {
+                    match stopped {
+                        Some(_) => {
+                            stats.interrupted = true;
+                            drain_deadline =
+                                Some(tokio::time::Instant::now() + opts.drain_timeout);
+                        }
+                        // A dropped sender means nobody can ask us to stop
+                        None => watching_stop = false,
+                    }
+                }
+                _ = sleep_until(drain_deadline.unwrap_or_else(tokio::time::Instant::now)),
+                    if drain_deadline.is_some() && !in_flight.is_empty() =>
+                {
+                    stats.unsent = stats.total - stats.successful - stats.failed;
+                    warn!(
+                        "Gave up on {} in-flight batches ({} events) after {:?}",
+                        in_flight.len(),
+                        stats.unsent,
+                        opts.drain_timeout
+                    );
+                    for events in pending.values() {
+                        dead_letter(&opts, events, "interrupted before delivery was confirmed");
+                    }
+                    break;
+                }
+                else => break,
+            }
+        }
+
+        stats.elapsed = started.elapsed();
+        stats.final_concurrency = concurrency;
+        stats.final_batch_size = batch_size.load(Ordering::Relaxed);
+        Ok(stats)
+    }
+
+    /// Send a batch (one request per pipeline group), returning each group's events and result
+    #[instrument(skip_all, fields(batch = batch_num, events = batch.len()))]
+    async fn send_with_retry(
+        &self,
+        batch_num: usize,
+        batch: Vec<Value>,
+        opts: &StreamOptions,
+    ) -> BatchOutcome {
+        let started = Instant::now();
+        let throttled = AtomicBool::new(false);
+        let groups = match &opts.pipeline_field {
+            Some(field) => group_by_pipeline(batch, field),
+            None => vec![(None, batch)],
+        };
+
+        let mut results = Vec::with_capacity(groups.len());
+        for (pipeline, events) in groups {
+            let pipeline = pipeline.as_deref().or(opts.pipeline.as_deref());
+
+            // Oversized payloads are split in half rather than retried as-is, down to
+            // single events; halves are sent in order and retried like any batch
+            let mut chunks = vec![events];
+            while let Some(events) = chunks.pop() {
+                let result = retry_with_backoff_notify(
+                    &opts.retry,
+                    || async {
+                        match self
+                            .stream_batch(&events, pipeline, opts.workspace.as_deref())
+                            .await
+                        {
+                            // Resending the same body can't succeed, so stop retrying
+                            Err(e @ Error::Server { status: 413, .. }) => Ok(Err(e)),
+                            Err(e @ Error::Server { status: 429, .. }) => {
+                                throttled.store(true, Ordering::Relaxed);
+                                Err(e)
+                            }
+                            result => result.map(Ok),
+                        }
+                    },
+                    |attempt, _| {
+                        opts.progress
+                            .on_retry(batch_num, attempt, opts.retry.max_attempts)
+                    },
+                )
+                .await;
+
+                let result = match result {
+                    Ok(Err(_)) if events.len() > 1 => {
+                        debug!(
+                            "Batch {}: payload too large, splitting {} events in half",
+                            batch_num,
+                            events.len()
+                        );
+                        throttled.store(true, Ordering::Relaxed);
+                        let mut first = events;
+                        let second = first.split_off(first.len() / 2);
+                        chunks.push(second);
+                        chunks.push(first);
+                        continue;
+                    }
+                    Ok(result) => result.map_err(anyhow::Error::from),
+                    Err(e) => Err(e),
+                };
+
+                // Put the routing field back so dead-lettered events can be replayed as-is
+                let events = match (&opts.pipeline_field, &pipeline) {
+                    (Some(field), Some(pipeline)) if result.is_err() => {
+                        restore_pipeline_field(events, field, pipeline)
+                    }
+                    _ => events,
+                };
+                results.push((events, result));
+            }
+        }
+
+        BatchOutcome {
+            batch_num,
+            groups: results,
+            latency: started.elapsed(),
+            throttled: throttled.into_inner(),
+        }
+    }
+}
+
+fn dead_letter(opts: &StreamOptions, events: &[Value], error: &str) {
+    if let Some(dead_letter) = &opts.dead_letter {
+        if let Err(e) = dead_letter.write(events, error) {
+            warn!(
+                "Failed to write {} events to {}: {}",
+                events.len(),
+                dead_letter.path().display(),
+                e
+            );
+        }
+    }
+}
+
+fn record_sent(opts: &StreamOptions, events: &[Value]) {
+    if let Some(sent_log) = &opts.sent_log {
+        if let Err(e) = sent_log.record(events) {
+            warn!(
+                "Failed to record {} sent events in {}: {}",
+                events.len(),
+                sent_log.path().display(),
+                e
+            );
+        }
+    }
+    if let Some(journal) = &opts.journal {
+        if let Err(e) = journal.record(events) {
+            warn!(
+                "Failed to journal {} sent events in {}: {}",
+                events.len(),
+                journal.path().display(),
+                e
+            );
+        }
+    }
+}
+
+fn restore_pipeline_field(mut events: Vec<Value>, field: &str, pipeline: &str) -> Vec<Value> {
+    for event in &mut events {
+        if let Some(obj) = event.as_object_mut() {
+            obj.insert(field.to_string(), Value::String(pipeline.to_string()));
+        }
+    }
+    events
+}
+
+/// Split a batch by the pipeline named in `field`, removing the field from each event.
+///
+/// Events without a string value for `field` are grouped under `None`.
+pub fn group_by_pipeline(batch: Vec<Value>, field: &str) -> Vec<(Option<String>, Vec<Value>)> {
+    let mut groups: Vec<(Option<String>, Vec<Value>)> = Vec::new();
+
+    for mut event in batch {
+        let pipeline = event
+            .as_object_mut()
+            .and_then(|obj| obj.remove(field))
+            .and_then(|value| value.as_str().map(String::from));
+
+        match groups.iter_mut().find(|(key, _)| *key == pipeline) {
+            Some((_, events)) => events.push(event),
+            None => groups.push((pipeline, vec![event])),
+        }
+    }
+
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adaptive_controller_aimd() {
+        let config = AdaptiveConfig {
+            max_concurrency: 4,
+            ..Default::default()
+        };
+        let mut controller = AdaptiveController::new(config, 100);
+        assert_eq!(controller.concurrency(), 1);
+
+        for _ in 0..10 {
+            controller.record(Duration::from_millis(50), true);
+        }
+        assert_eq!(controller.concurrency(), 4);
+        assert_eq!(controller.batch_size(), 600);
+
+        controller.record(Duration::from_millis(50), false);
+        assert_eq!(controller.concurrency(), 2);
+        assert_eq!(controller.batch_size(), 300);
+
+        controller.record(Duration::from_secs(2), true);
+        assert_eq!(controller.concurrency(), 1);
+        assert_eq!(controller.batch_size(), 300);
+    }
+
+    #[test]
+    fn test_adaptive_config_capped_below_min_batch_size() {
+        let config = AdaptiveConfig::default().capped(Some(5));
+        assert_eq!((config.min_batch_size, config.max_batch_size), (5, 5));
+
+        let mut controller = AdaptiveController::new(config, 100);
+        assert_eq!(controller.batch_size(), 5);
+        controller.record(Duration::from_millis(50), true);
+        assert_eq!(controller.batch_size(), 5);
+        controller.record(Duration::from_millis(50), false);
+        assert_eq!(controller.batch_size(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_batch_is_reported_in_stats() {
+        let config = crate::core::config::Config::builder()
+            .api_key("test_key_12345678")
+            .workspace("test-workspace")
+            .build()
+            .unwrap();
+        let client = Client::new(config).unwrap();
+        let events = (0..3).map(|i| serde_json::json!({"source": "s", "data": {"n": i}}));
+        let opts = StreamOptions {
+            batch_size: 2,
+            limits: BatchLimits {
+                max_events: Some(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let stats = client.stream_all(stream::iter(events), opts).await.unwrap();
+        assert!(stats.validation_error.unwrap().contains("exceeds limit of 1 events"));
+        assert_eq!((stats.total, stats.failed, stats.successful), (2, 2, 0));
+        assert_eq!(stats.batches, 0);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drains_when_every_slot_is_busy() {
+        // Accepts connections and never answers, so the one batch stays in flight
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+        let config = crate::core::config::Config::builder()
+            .api_key("test_key_12345678")
+            .workspace("test-workspace")
+            .ingest_url(format!("http://{}", addr))
+            .build()
+            .unwrap();
+        let client = Client::new(config).unwrap();
+        let (stop_tx, stop_rx) = watch::channel(false);
+        let events = (0..10).map(|i| {
+            let timestamp = "2024-01-01T00:00:00Z";
+            serde_json::json!({"source": "s", "timestamp": timestamp, "data": {"n": i}})
+        });
+        let opts = StreamOptions {
+            batch_size: 1,
+            concurrency: 1,
+            shutdown: Some(stop_rx),
+            drain_timeout: Duration::from_millis(100),
+            ..Default::default()
+        };
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            let _ = stop_tx.send(true);
+        });
+        let run = client.stream_all(stream::iter(events), opts);
+        let stats = tokio::time::timeout(Duration::from_secs(5), run)
+            .await
+            .expect("drain timer never started")
+            .unwrap();
+        assert!(stats.interrupted);
+        assert_eq!(stats.unsent, 1);
+    }
+}
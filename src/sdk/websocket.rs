@@ -1,19 +1,54 @@
 //! WebSocket streaming support for real-time data ingestion (v0.3.0)
 use anyhow::{Context, Result};
-use futures_util::{SinkExt, StreamExt};
+use futures_util::{FutureExt, SinkExt, StreamExt};
 use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpStream;
 use tokio::sync::mpsc;
-use tokio_tungstenite::{connect_async, tungstenite::Message};
-use tracing::{debug, error, info};
+use tokio::sync::mpsc::error::TrySendError;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::{
+    connect_async_tls_with_config, tungstenite::Message, Connector, MaybeTlsStream,
+    WebSocketStream,
+};
+use tracing::{debug, error, info, warn};
 use url::Url;
 
 use crate::core::config::Config;
+use crate::core::error::Error;
+use crate::core::tls::TlsSettings;
+
+/// Message types the server uses to reject the auth frame
+const AUTH_REJECTED_TYPES: &[&str] = &["auth_error", "auth_failed"];
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// How long `send_events` waits for outstanding acks after the last send
+const ACK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Received events buffered between the socket reader and the event callback
+const DEFAULT_BUFFER_SIZE: usize = 1024;
+
+/// What the reader does when the event callback falls behind and the buffer is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Stop reading until the callback catches up (pings are still answered once space frees)
+    #[default]
+    Backpressure,
+    /// Drop the event and count it in `dropped_events`
+    Drop,
+}
 
 /// WebSocket client for real-time data streaming
 pub struct WebSocketClient {
     config: Arc<Config>,
     reconnect_interval: u64,
+    buffer_size: usize,
+    overflow: OverflowPolicy,
+    dropped: Arc<AtomicU64>,
 }
 
 impl WebSocketClient {
@@ -22,23 +57,69 @@ impl WebSocketClient {
         Self {
             config: Arc::new(config),
             reconnect_interval: 5,
+            buffer_size: DEFAULT_BUFFER_SIZE,
+            overflow: OverflowPolicy::default(),
+            dropped: Arc::new(AtomicU64::new(0)),
         }
     }
 
+    /// Set the event buffer size and what happens when it fills up
+    pub fn with_buffer(mut self, size: usize, overflow: OverflowPolicy) -> Self {
+        self.buffer_size = size.max(1);
+        self.overflow = overflow;
+        self
+    }
+
+    /// Events dropped so far under `OverflowPolicy::Drop`
+    pub fn dropped_events(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
     /// Connect to WebSocket endpoint and stream events
     pub async fn connect_and_stream<F>(&self, mut on_event: F) -> Result<()>
     where
         F: FnMut(Value) -> Result<()> + Send + 'static,
     {
         let ws_url = self.build_ws_url()?;
+        let connector = TlsSettings::from_config(&self.config)?
+            .native_tls_connector()?
+            .map(Connector::NativeTls);
         info!("Connecting to WebSocket: {}", ws_url);
 
+        // Run the callback on its own task so a slow consumer can't stall the reader
+        let (tx, mut rx) = mpsc::channel::<Value>(self.buffer_size);
+        let processor = tokio::spawn(async move {
+            while let Some(value) = rx.recv().await {
+                if let Err(e) = on_event(value) {
+                    error!("Error processing event: {}", e);
+                }
+            }
+        });
+
+        let result = self.reconnect_loop(&ws_url, connector, &tx).await;
+
+        // Let the processor drain whatever is still buffered
+        drop(tx);
+        processor.await.context("WebSocket event processor panicked")?;
+        result
+    }
+
+    async fn reconnect_loop(
+        &self,
+        ws_url: &str,
+        connector: Option<Connector>,
+        tx: &mpsc::Sender<Value>,
+    ) -> Result<()> {
         loop {
-            match self.connect_once(&ws_url, &mut on_event).await {
+            match self.connect_once(ws_url, connector.clone(), tx).await {
                 Ok(_) => {
                     info!("WebSocket connection closed normally");
                     break;
                 }
+                Err(e) if is_auth_error(&e) => {
+                    error!("WebSocket authentication failed, not reconnecting: {}", e);
+                    return Err(e.context("WebSocket authentication failed"));
+                }
                 Err(e) => {
                     error!("WebSocket error: {}. Reconnecting in {}s...", e, self.reconnect_interval);
                     tokio::time::sleep(tokio::time::Duration::from_secs(self.reconnect_interval)).await;
@@ -49,22 +130,15 @@ impl WebSocketClient {
         Ok(())
     }
 
-    async fn connect_once<F>(&self, url: &str, on_event: &mut F) -> Result<()>
-    where
-        F: FnMut(Value) -> Result<()>,
-    {
-        let url = Url::parse(url)?;
-        let (ws_stream, _) = connect_async(url).await?;
+    async fn connect_once(
+        &self,
+        url: &str,
+        connector: Option<Connector>,
+        tx: &mpsc::Sender<Value>,
+    ) -> Result<()> {
+        let ws_stream = self.open(url, connector, &self.config.workspace).await?;
         let (mut write, mut read) = ws_stream.split();
-
-        // Send authentication message
-        let auth_msg = serde_json::json!({
-            "type": "auth",
-            "api_key": self.config.api_key,
-            "workspace": self.config.workspace,
-        });
-        write.send(Message::Text(auth_msg.to_string())).await?;
-        info!("WebSocket authenticated");
+        let mut authenticated = false;
 
         // Listen for messages
         while let Some(msg) = read.next().await {
@@ -72,17 +146,25 @@ impl WebSocketClient {
                 Ok(Message::Text(text)) => {
                     debug!("Received WebSocket message: {}", text);
                     match serde_json::from_str::<Value>(&text) {
+                        Ok(value) if value["type"] == "auth_ok" => {
+                            authenticated = true;
+                            info!("WebSocket authenticated");
+                        }
                         Ok(value) => {
-                            if let Err(e) = on_event(value) {
-                                error!("Error processing event: {}", e);
+                            if let Some(err) = auth_rejection(&value) {
+                                return Err(err.into());
                             }
+                            self.dispatch(tx, value).await?
                         }
                         Err(e) => {
                             error!("Failed to parse WebSocket message: {}", e);
                         }
                     }
                 }
-                Ok(Message::Close(_)) => {
+                Ok(Message::Close(frame)) => {
+                    if let Some(err) = close_rejection(frame.as_ref(), authenticated) {
+                        return Err(err.into());
+                    }
                     info!("WebSocket connection closed");
                     break;
                 }
@@ -99,21 +181,284 @@ impl WebSocketClient {
         Ok(())
     }
 
+    /// Send events over a single connection and wait for the server to ack each one.
+    ///
+    /// Each event is framed as `{"type": "event", "seq": n, "data": ...}`; the server
+    /// answers `{"type": "ack", "seq": n}` or `{"type": "nack", "seq": n, "error": ...}`.
+    /// Events still outstanding when the connection drops or `ACK_TIMEOUT` passes are
+    /// reported as `Delivery::Unacked` so callers can dead-letter or resend them.
+    pub async fn send_events(
+        &self,
+        events: &[Value],
+        pipeline: Option<&str>,
+        workspace: Option<&str>,
+    ) -> Result<SendReport> {
+        let ws_url = self.build_ws_url()?;
+        let connector = TlsSettings::from_config(&self.config)?
+            .native_tls_connector()?
+            .map(Connector::NativeTls);
+        let workspace = workspace.unwrap_or(&self.config.workspace);
+
+        let ws_stream = self.open(&ws_url, connector, workspace).await?;
+        let (mut write, mut read) = ws_stream.split();
+
+        let mut tracker = AckTracker::new(events.len());
+
+        for (index, event) in events.iter().enumerate() {
+            let seq = index as u64;
+            let mut frame = serde_json::json!({ "type": "event", "seq": seq, "data": event });
+            if let Some(pipeline_id) = pipeline {
+                frame["pipeline_id"] = Value::String(pipeline_id.to_string());
+            }
+
+            if let Err(e) = write.send(Message::Text(frame.to_string())).await {
+                warn!("WebSocket send failed after {} events: {}", index, e);
+                return Ok(tracker.finish());
+            }
+            tracker.outstanding.insert(seq, index);
+
+            // Process any acks that already arrived without waiting for more
+            while let Some(Some(msg)) = read.next().now_or_never() {
+                if !tracker.handle(msg)? {
+                    return Ok(tracker.finish());
+                }
+            }
+        }
+
+        let deadline = tokio::time::Instant::now() + ACK_TIMEOUT;
+        while !tracker.outstanding.is_empty() {
+            match tokio::time::timeout_at(deadline, read.next()).await {
+                Ok(Some(msg)) => {
+                    if !tracker.handle(msg)? {
+                        break;
+                    }
+                }
+                Ok(None) => break,
+                Err(_) => {
+                    warn!(
+                        "Timed out waiting for {} WebSocket acks",
+                        tracker.outstanding.len()
+                    );
+                    break;
+                }
+            }
+        }
+
+        let _ = write.send(Message::Close(None)).await;
+        let report = tracker.finish();
+        debug!(
+            "WebSocket send: {} acked, {} rejected, {} unacked",
+            report.acked(),
+            report.rejected(),
+            report.unacked()
+        );
+        Ok(report)
+    }
+
+    /// Connect and send the auth frame for `workspace`
+    async fn open(
+        &self,
+        url: &str,
+        connector: Option<Connector>,
+        workspace: &str,
+    ) -> Result<WsStream> {
+        let url = Url::parse(url)?;
+        let (mut ws_stream, _) =
+            connect_async_tls_with_config(url.as_str(), None, false, connector).await?;
+
+        let auth_msg = serde_json::json!({
+            "type": "auth",
+            "api_key": self.config.api_key,
+            "workspace": workspace,
+        });
+        ws_stream.send(Message::Text(auth_msg.to_string())).await?;
+        Ok(ws_stream)
+    }
+
+    /// Hand an event to the processor task according to the overflow policy
+    async fn dispatch(&self, tx: &mpsc::Sender<Value>, value: Value) -> Result<()> {
+        match self.overflow {
+            OverflowPolicy::Backpressure => tx
+                .send(value)
+                .await
+                .map_err(|_| anyhow::anyhow!("WebSocket event processor stopped")),
+            OverflowPolicy::Drop => match tx.try_send(value) {
+                Ok(()) => Ok(()),
+                Err(TrySendError::Full(_)) => {
+                    let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                    warn!("Event buffer full, dropped event ({} dropped so far)", dropped);
+                    Ok(())
+                }
+                Err(TrySendError::Closed(_)) => {
+                    Err(anyhow::anyhow!("WebSocket event processor stopped"))
+                }
+            },
+        }
+    }
+
     fn build_ws_url(&self) -> Result<String> {
         let base = self.config.ingest_url.replace("https://", "wss://").replace("http://", "ws://");
         Ok(format!("{}/ws/stream", base))
     }
 }
 
-/// Stream events via WebSocket with automatic reconnection
-pub async fn stream_websocket(config: Config, _events: Vec<Value>) -> Result<()> {
-    let client = WebSocketClient::new(config);
-    
-    client.connect_and_stream(|event| {
-        debug!("Processing WebSocket event: {:?}", event);
-        Ok(())
-    }).await?;
+fn auth_error(reason: &str) -> Error {
+    Error::Auth {
+        status: 401,
+        body: reason.to_string(),
+        request_id: None,
+    }
+}
+
+/// Outcome of a single event sent with `WebSocketClient::send_events`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Delivery {
+    /// The server acknowledged the event
+    Acked,
+    /// The server rejected the event with this reason
+    Rejected(String),
+    /// No ack arrived before the connection closed or the ack timeout passed
+    Unacked,
+}
+
+/// Per-event results of `WebSocketClient::send_events`, in input order
+#[derive(Debug, Clone)]
+pub struct SendReport {
+    pub outcomes: Vec<Delivery>,
+}
+
+impl SendReport {
+    pub fn acked(&self) -> usize {
+        self.count(|d| *d == Delivery::Acked)
+    }
 
+    pub fn rejected(&self) -> usize {
+        self.count(|d| matches!(d, Delivery::Rejected(_)))
+    }
+
+    pub fn unacked(&self) -> usize {
+        self.count(|d| *d == Delivery::Unacked)
+    }
+
+    /// Indices of events that were rejected or never acknowledged
+    pub fn failed_indices(&self) -> Vec<usize> {
+        self.outcomes
+            .iter()
+            .enumerate()
+            .filter(|(_, d)| **d != Delivery::Acked)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    fn count(&self, f: impl Fn(&Delivery) -> bool) -> usize {
+        self.outcomes.iter().filter(|d| f(d)).count()
+    }
+}
+
+/// Matches server acks to the events they reference by sequence id
+struct AckTracker {
+    outcomes: Vec<Delivery>,
+    /// Sequence id -> index of events sent but not yet acked
+    outstanding: HashMap<u64, usize>,
+    authenticated: bool,
+}
+
+impl AckTracker {
+    fn new(len: usize) -> Self {
+        Self {
+            outcomes: vec![Delivery::Unacked; len],
+            outstanding: HashMap::new(),
+            authenticated: false,
+        }
+    }
+
+    /// Apply one incoming message; returns `false` once the connection is closing
+    fn handle(&mut self, msg: tokio_tungstenite::tungstenite::Result<Message>) -> Result<bool> {
+        let text = match msg {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Close(frame)) => {
+                if let Some(err) = close_rejection(frame.as_ref(), self.authenticated) {
+                    return Err(err.into());
+                }
+                return Ok(false);
+            }
+            Ok(_) => return Ok(true),
+            Err(e) => {
+                warn!("WebSocket error while awaiting acks: {}", e);
+                return Ok(false);
+            }
+        };
+
+        let Ok(value) = serde_json::from_str::<Value>(&text) else {
+            return Ok(true);
+        };
+        if let Some(err) = auth_rejection(&value) {
+            return Err(err.into());
+        }
+
+        let seq = value["seq"].as_u64();
+        match (value["type"].as_str(), seq) {
+            (Some("auth_ok"), _) => self.authenticated = true,
+            (Some("ack"), Some(seq)) => self.resolve(seq, Delivery::Acked),
+            (Some("nack"), Some(seq)) => {
+                let reason = value["error"].as_str().unwrap_or("rejected by server");
+                self.resolve(seq, Delivery::Rejected(reason.to_string()));
+            }
+            _ => debug!("Ignoring WebSocket message: {}", text),
+        }
+        Ok(true)
+    }
+
+    fn resolve(&mut self, seq: u64, delivery: Delivery) {
+        match self.outstanding.remove(&seq) {
+            Some(index) => self.outcomes[index] = delivery,
+            None => debug!("Ack for unknown sequence id {}", seq),
+        }
+    }
+
+    fn finish(self) -> SendReport {
+        SendReport {
+            outcomes: self.outcomes,
+        }
+    }
+}
+
+/// The auth error carried by a server message that rejects our credentials
+fn auth_rejection(value: &Value) -> Option<Error> {
+    let message_type = value["type"].as_str()?;
+    if !AUTH_REJECTED_TYPES.contains(&message_type) {
+        return None;
+    }
+
+    let reason = value["message"]
+        .as_str()
+        .or(value["error"].as_str())
+        .unwrap_or("auth rejected by server");
+    Some(auth_error(reason))
+}
+
+/// A policy close before any auth ack is how some gateways reject keys
+fn close_rejection(frame: Option<&CloseFrame<'_>>, authenticated: bool) -> Option<Error> {
+    let frame = frame.filter(|_| !authenticated)?;
+    matches!(u16::from(frame.code), 1008 | 4001 | 4003).then(|| auth_error(&frame.reason))
+}
+
+/// Auth rejections are permanent, so the reconnect loop must stop on them
+fn is_auth_error(err: &anyhow::Error) -> bool {
+    matches!(err.downcast_ref::<Error>(), Some(Error::Auth { .. }))
+}
+
+/// Send events over a WebSocket connection
+pub async fn stream_websocket(config: Config, events: Vec<Value>) -> Result<()> {
+    let client = WebSocketClient::new(config);
+    let report = client.send_events(&events, None, None).await?;
+    if report.acked() < events.len() {
+        anyhow::bail!(
+            "{} of {} events were not acknowledged",
+            events.len() - report.acked(),
+            events.len()
+        );
+    }
     Ok(())
 }
 